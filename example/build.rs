@@ -0,0 +1,26 @@
+use std::{env, fs, path::PathBuf};
+
+use prost_convert_build::{Config, NativeConfig};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = Config::new();
+    // Don't require protoc to be installed to build the example.
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+    let includes = [PathBuf::from("proto"), protoc_bin_vendored::include_path()?];
+    let fds = config.load_fds(&["proto/user.proto", "proto/shop.proto"], &includes)?;
+
+    prost_convert_build::emit_manifest(&fds)?;
+    let native = NativeConfig::from_toml(&fs::read_to_string("native.toml")?)?.generate(&fds)?;
+    fs::write(
+        PathBuf::from(env::var("OUT_DIR")?).join("native.rs"),
+        native,
+    )?;
+    println!("cargo:rerun-if-changed=proto");
+    println!("cargo:rerun-if-changed=native.toml");
+
+    // Only the server trait is implemented by the native services.
+    tonic_build::configure()
+        .build_client(false)
+        .compile_fds_with_config(config, fds)?;
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! Native types converted from the code generated by `prost-convert-build`.
+
+/// The code generated by prost.
+#[allow(missing_docs)]
+pub mod proto {
+    pub mod example {
+        pub mod account {
+            include!(concat!(env!("OUT_DIR"), "/example.account.rs"));
+        }
+        pub mod shop {
+            include!(concat!(env!("OUT_DIR"), "/example.shop.rs"));
+        }
+    }
+}
+
+/// The native types generated from the proto files.
+pub mod native {
+    include!(concat!(env!("OUT_DIR"), "/native.rs"));
+}
@@ -0,0 +1,80 @@
+use prost::Message;
+use prost_convert::{
+    codec::{
+        decode, decode_native, decode_native_length_delimited, encode, encode_native,
+        encode_native_length_delimited, encode_native_to_vec, CodecError,
+    },
+    ProstConvertError,
+};
+use prost_convert_example::{native::example::shop as native, proto::example::shop as proto};
+
+fn item() -> native::Item {
+    native::Item {
+        sku: "donut".to_owned(),
+        quantity: 12,
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let mut buf = Vec::new();
+    encode_native::<proto::Item, _>(&item(), &mut buf).unwrap();
+
+    assert_eq!(buf, encode_native_to_vec::<proto::Item, _>(&item()));
+    let decoded: native::Item = decode_native::<proto::Item, _>(buf.as_slice()).unwrap();
+    assert_eq!(decoded, item());
+}
+
+#[test]
+fn inferred_proto_type() {
+    let mut buf = Vec::new();
+    encode(&item(), &mut buf).unwrap();
+
+    assert_eq!(decode::<native::Item>(buf.as_slice()).unwrap(), item());
+}
+
+#[test]
+fn length_delimited_roundtrip() {
+    let mut buf = Vec::new();
+    encode_native_length_delimited::<proto::Item, _>(&item(), &mut buf).unwrap();
+    encode_native_length_delimited::<proto::Item, _>(&item(), &mut buf).unwrap();
+
+    let mut bytes = buf.as_slice();
+    for _ in 0..2 {
+        let decoded: native::Item =
+            decode_native_length_delimited::<proto::Item, _>(&mut bytes).unwrap();
+        assert_eq!(decoded, item());
+    }
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn encode_error() {
+    let mut buf = [0u8; 2];
+    let err = encode_native::<proto::Item, _>(&item(), &mut buf.as_mut_slice()).unwrap_err();
+
+    assert!(matches!(err, CodecError::Encode(_)));
+}
+
+#[test]
+fn decode_error() {
+    let err = decode_native::<proto::Item, native::Item>(&[0xff, 0xff][..]).unwrap_err();
+
+    assert!(matches!(err, CodecError::Decode(_)));
+}
+
+#[test]
+fn convert_error() {
+    let bytes = proto::ListOrdersRequest {
+        status: proto::Status::Unspecified as i32,
+    }
+    .encode_to_vec();
+    let err =
+        decode_native::<proto::ListOrdersRequest, native::ListOrdersRequest>(bytes.as_slice())
+            .unwrap_err();
+
+    assert!(matches!(
+        err,
+        CodecError::Convert(ProstConvertError::UnknownEnumValue(0))
+    ));
+}
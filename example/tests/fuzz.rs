@@ -0,0 +1,64 @@
+use std::time::SystemTime;
+
+use prost::Message;
+use prost_convert::{testing::fuzz_try_from_proto, ProstConvertError, TryIntoNative};
+use prost_convert_example::{native::example::shop as native, proto::example::shop as proto};
+
+fn order() -> proto::Order {
+    proto::Order {
+        id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_owned(),
+        ip: "127.0.0.1".to_owned(),
+        status: proto::Status::Shipped as i32,
+        items: vec![proto::Item {
+            sku: "donut".to_owned(),
+            quantity: 12,
+        }],
+        created_at: Some(SystemTime::UNIX_EPOCH.into()),
+        delivery_delay: Some(prost_types::Duration {
+            seconds: 3600,
+            nanos: 0,
+        }),
+        payment: Some(proto::order::Payment::Voucher("SPRING".to_owned())),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn corrupted_bytes_never_panic() {
+    let bytes = order().encode_to_vec();
+
+    for len in 0..=bytes.len() {
+        fuzz_try_from_proto::<proto::Order, native::Order>(&bytes[..len]);
+    }
+    for index in 0..bytes.len() {
+        for bit in 0..8 {
+            let mut corrupted = bytes.clone();
+            corrupted[index] ^= 1 << bit;
+            fuzz_try_from_proto::<proto::Order, native::Order>(&corrupted);
+        }
+    }
+}
+
+#[test]
+fn out_of_range_values_are_errors() {
+    for status in [i32::MIN, -1, 3, i32::MAX] {
+        let native: Result<native::Status, _> = status.try_into_native();
+        assert!(
+            matches!(native, Err(ProstConvertError::UnknownEnumValue(value)) if value == status)
+        );
+    }
+
+    let proto = proto::Order {
+        created_at: Some(prost_types::Timestamp {
+            seconds: i64::MIN,
+            nanos: i32::MIN,
+        }),
+        delivery_delay: Some(prost_types::Duration {
+            seconds: i64::MIN,
+            nanos: i32::MIN,
+        }),
+        ..order()
+    };
+    let native: Result<native::Order, _> = proto.try_into_native();
+    assert!(native.is_err());
+}
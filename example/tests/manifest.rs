@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use prost_convert::{IntoProto, ProstConvert, ProstConvertError, TryIntoNative};
+use prost_convert_example::proto::example::account as proto;
+
+#[derive(Debug, Clone, PartialEq, ProstConvert)]
+#[prost_convert(src = "prost_convert_example::proto::example::account::Address")]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ProstConvert)]
+#[prost_convert(src = "prost_convert_example::proto::example::account::user::Contact")]
+enum Contact {
+    Email(String),
+    Phone(String),
+}
+
+#[derive(Debug, Clone, PartialEq, ProstConvert)]
+#[prost_convert(src = "prost_convert_example::proto::example::account::User")]
+struct User {
+    name: String,
+    // Never unset in proto, the manifest tells the derive to always convert it into `Some`.
+    nickname: Option<String>,
+    age: Option<u32>,
+    address: Address,
+    billing_address: Option<Address>,
+    tags: Vec<String>,
+    labels: HashMap<String, String>,
+    contact: Option<Contact>,
+}
+
+// The fields left to a flattened struct are checked with the manifest.
+#[derive(Debug, Clone, PartialEq, ProstConvert)]
+#[prost_convert(src = "prost_convert_example::proto::example::account::Address")]
+struct Place {
+    street: String,
+    #[prost_convert(flatten)]
+    city: City,
+}
+
+#[derive(Debug, Clone, PartialEq, ProstConvert)]
+#[prost_convert(
+    src = "prost_convert_example::proto::example::account::Address",
+    proto_default
+)]
+struct City {
+    city: String,
+}
+
+// Hand-written proto types, which share their name with a message of the manifest.
+mod handwritten {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct User {
+        pub name: String,
+        pub nickname: Option<String>,
+        pub email: String,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, ProstConvert)]
+#[prost_convert(src = "handwritten::User", skip_manifest)]
+struct Member {
+    name: String,
+    nickname: Option<String>,
+    email: String,
+}
+
+fn address() -> proto::Address {
+    proto::Address {
+        street: "1 main street".to_owned(),
+        city: "Springfield".to_owned(),
+    }
+}
+
+fn user() -> proto::User {
+    proto::User {
+        name: "homer".to_owned(),
+        nickname: String::new(),
+        age: Some(39),
+        address: Some(address()),
+        billing_address: None,
+        tags: vec!["dad".to_owned()],
+        labels: HashMap::from([("job".to_owned(), "safety inspector".to_owned())]),
+        contact: Some(proto::user::Contact::Email("homer@example.com".to_owned())),
+    }
+}
+
+#[test]
+fn plain_field_into_option() {
+    let native: User = user().try_into_native().unwrap();
+
+    assert_eq!(native.nickname, Some(String::new()));
+    assert_eq!(native.billing_address, None);
+    assert_eq!(
+        native.contact,
+        Some(Contact::Email("homer@example.com".to_owned()))
+    );
+    assert_eq!(user(), native.into_proto());
+}
+
+#[test]
+fn none_into_zero_value() {
+    let mut native: User = user().try_into_native().unwrap();
+    native.nickname = None;
+    let proto: proto::User = native.into_proto();
+
+    assert_eq!(proto.nickname, "");
+}
+
+#[test]
+fn optional_message_is_required() {
+    let proto = proto::User {
+        address: None,
+        ..user()
+    };
+    let native: Result<User, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::MissingRequiredField)
+    ));
+}
+
+#[test]
+fn flattened_fields() {
+    let native: Place = address().try_into_native().unwrap();
+
+    assert_eq!(native.city.city, "Springfield");
+    assert_eq!(address(), native.into_proto());
+}
+
+#[test]
+fn handwritten_type_is_not_in_manifest() {
+    let proto = handwritten::User {
+        name: "marge".to_owned(),
+        nickname: None,
+        email: "marge@example.com".to_owned(),
+    };
+    let native: Member = proto.clone().try_into_native().unwrap();
+
+    assert_eq!(native.nickname, None);
+    assert_eq!(proto, native.into_proto());
+}
+
+#[test]
+fn unknown_field() {
+    // The path of the manifest is only set while compiling this crate.
+    std::env::set_var("PROST_CONVERT_MANIFEST", env!("PROST_CONVERT_MANIFEST"));
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_example::{native::example::shop as native, proto::example::shop as proto};
+
+fn order() -> proto::Order {
+    proto::Order {
+        id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_owned(),
+        ip: "127.0.0.1".to_owned(),
+        status: proto::Status::Shipped as i32,
+        items: vec![proto::Item {
+            sku: "donut".to_owned(),
+            quantity: 12,
+        }],
+        history: HashMap::from([("monday".to_owned(), proto::Status::Pending as i32)]),
+        created_at: Some(SystemTime::UNIX_EPOCH.into()),
+        delivery_delay: Some(prost_types::Duration {
+            seconds: 3600,
+            nanos: 0,
+        }),
+        note: None,
+        replaces: Some(Box::new(proto::Order {
+            replaces: None,
+            payment: Some(proto::order::Payment::Voucher("SPRING".to_owned())),
+            ..order_without_payment()
+        })),
+        priority: Some(1),
+        payment: Some(proto::order::Payment::Card(proto::order::Card {
+            number: "4242".to_owned(),
+        })),
+    }
+}
+
+fn order_without_payment() -> proto::Order {
+    proto::Order {
+        id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_owned(),
+        ip: "::1".to_owned(),
+        status: proto::Status::Pending as i32,
+        items: vec![],
+        history: HashMap::new(),
+        created_at: Some(SystemTime::UNIX_EPOCH.into()),
+        delivery_delay: Some(prost_types::Duration::default()),
+        note: Some("fragile".to_owned()),
+        replaces: None,
+        priority: None,
+        payment: None,
+    }
+}
+
+#[test]
+fn generated_types_roundtrip() {
+    let native: native::Order = order().try_into_native().unwrap();
+
+    assert_eq!(
+        native.id.to_string(),
+        "67e55044-10b1-426f-9247-bb680e5fe0c8"
+    );
+    assert!(native.ip.is_loopback());
+    assert_eq!(native.status, native::Status::Shipped);
+    assert_eq!(native.created_at, SystemTime::UNIX_EPOCH);
+    assert_eq!(native.delivery_delay, Duration::from_secs(3600));
+    assert_eq!(
+        native.payment,
+        native::order::Payment::Card(native::order::Card {
+            number: "4242".to_owned()
+        })
+    );
+    assert_eq!(order(), native.into_proto());
+}
+
+#[test]
+fn oneof_is_required() {
+    let native: Result<native::Order, _> = order_without_payment().try_into_native();
+
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::MissingRequiredField)
+    ));
+}
+
+#[test]
+fn unspecified_variant_is_dropped() {
+    let proto = proto::Order {
+        status: proto::Status::Unspecified as i32,
+        ..order()
+    };
+    let native: Result<native::Order, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::UnknownEnumValue(0))
+    ));
+
+    let native: Result<native::Status, _> = proto::Status::Unspecified.try_into_native();
+
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::UnknownEnumValue(0))
+    ));
+}
@@ -0,0 +1,142 @@
+// Tonic handlers and streams return a `Status`.
+#![allow(clippy::result_large_err)]
+
+use std::time::SystemTime;
+
+use futures::{executor::block_on, stream, StreamExt};
+use prost_convert::{stream::TryIntoNativeStreamExt, tonic::BoxStream, TryIntoNative};
+use prost_convert_example::{
+    native::example::shop::{self as native, OrderService, OrderServiceAdapter},
+    proto::example::shop::{self as proto, order_service_server::OrderService as _},
+};
+use tonic::{Code, Request, Response, Status};
+
+fn order(status: native::Status, items: Vec<native::Item>) -> native::Order {
+    let proto = proto::Order {
+        id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_owned(),
+        ip: "127.0.0.1".to_owned(),
+        status: proto::Status::Pending as i32,
+        created_at: Some(SystemTime::UNIX_EPOCH.into()),
+        delivery_delay: Some(prost_types::Duration::default()),
+        payment: Some(proto::order::Payment::Voucher("SPRING".to_owned())),
+        ..Default::default()
+    };
+    native::Order {
+        status,
+        items,
+        ..proto.try_into_native().unwrap()
+    }
+}
+
+/// A service which never touches a prost type.
+struct Shop;
+
+#[prost_convert::tonic::async_trait]
+impl OrderService for Shop {
+    type ListOrdersStream = BoxStream<native::Order>;
+    type WatchOrdersStream = BoxStream<native::Order>;
+
+    async fn get_order(
+        &self,
+        request: Request<native::GetOrderRequest>,
+    ) -> Result<Response<native::Order>, Status> {
+        let mut order = order(native::Status::Pending, Vec::new());
+        order.id = request.into_inner().id.parse().unwrap();
+        Ok(Response::new(order))
+    }
+
+    async fn list_orders(
+        &self,
+        request: Request<native::ListOrdersRequest>,
+    ) -> Result<Response<Self::ListOrdersStream>, Status> {
+        let status = request.into_inner().status;
+        let orders = vec![Ok(order(status, Vec::new())), Ok(order(status, Vec::new()))];
+        Ok(Response::new(Box::pin(stream::iter(orders))))
+    }
+
+    async fn add_items(
+        &self,
+        request: Request<BoxStream<native::Item>>,
+    ) -> Result<Response<native::Order>, Status> {
+        let mut items = Vec::new();
+        let mut stream = request.into_inner();
+        while let Some(item) = stream.next().await {
+            items.push(item?);
+        }
+        Ok(Response::new(order(native::Status::Pending, items)))
+    }
+
+    async fn watch_orders(
+        &self,
+        request: Request<BoxStream<native::GetOrderRequest>>,
+    ) -> Result<Response<Self::WatchOrdersStream>, Status> {
+        let orders = request.into_inner().map(|request| {
+            request.map(|request| {
+                let mut order = order(native::Status::Shipped, Vec::new());
+                order.id = request.id.parse().unwrap();
+                order
+            })
+        });
+        Ok(Response::new(Box::pin(orders)))
+    }
+}
+
+#[test]
+fn unary() {
+    let request = Request::new(proto::GetOrderRequest {
+        id: "936da01f-9abd-4d9d-80c7-02af85c822a8".to_owned(),
+    });
+    let order = block_on(OrderServiceAdapter(Shop).get_order(request))
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(order.id, "936da01f-9abd-4d9d-80c7-02af85c822a8");
+    assert_eq!(order.status, proto::Status::Pending as i32);
+}
+
+#[test]
+fn invalid_request() {
+    let request = Request::new(proto::ListOrdersRequest {
+        status: proto::Status::Unspecified as i32,
+    });
+    let status = block_on(OrderServiceAdapter(Shop).list_orders(request)).unwrap_err();
+
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
+
+#[test]
+fn server_streaming() {
+    let request = Request::new(proto::ListOrdersRequest {
+        status: proto::Status::Shipped as i32,
+    });
+    let orders = block_on(OrderServiceAdapter(Shop).list_orders(request))
+        .unwrap()
+        .into_inner();
+    let orders = block_on(orders.collect::<Vec<_>>());
+
+    assert_eq!(orders.len(), 2);
+    for order in orders {
+        assert_eq!(order.unwrap().status, proto::Status::Shipped as i32);
+    }
+}
+
+#[test]
+fn client_streaming() {
+    // `tonic::Streaming` can only be built from a request body, the adapter converts it like any
+    // other stream.
+    let items = stream::iter(vec![
+        Ok(proto::Item {
+            sku: "donut".to_owned(),
+            quantity: 12,
+        }),
+        Ok(proto::Item {
+            sku: "coffee".to_owned(),
+            quantity: 1,
+        }),
+    ]);
+    let request = Request::new(Box::pin(items.try_into_native_stream()) as BoxStream<native::Item>);
+    let order = block_on(Shop.add_items(request)).unwrap().into_inner();
+
+    assert_eq!(order.items.len(), 2);
+    assert_eq!(order.items[1].sku, "coffee");
+}
@@ -0,0 +1,78 @@
+use futures::{executor::block_on, stream, StreamExt};
+use prost_convert::{
+    stream::{OnError, TryIntoNativeStreamExt},
+    ProstConvertError,
+};
+use prost_convert_example::{native::example::shop as native, proto::example::shop as proto};
+
+fn requests() -> Vec<Result<proto::ListOrdersRequest, ProstConvertError>> {
+    vec![
+        Ok(proto::ListOrdersRequest {
+            status: proto::Status::Pending as i32,
+        }),
+        Ok(proto::ListOrdersRequest {
+            status: proto::Status::Unspecified as i32,
+        }),
+        Err(ProstConvertError::MissingRequiredField),
+        Ok(proto::ListOrdersRequest {
+            status: proto::Status::Shipped as i32,
+        }),
+    ]
+}
+
+fn statuses(on_error: OnError) -> Vec<Result<native::Status, ProstConvertError>> {
+    let stream = stream::iter(requests())
+        .try_into_native_stream_with::<native::ListOrdersRequest>(on_error)
+        .map(|request| request.map(|request| request.status));
+    block_on(stream.collect())
+}
+
+#[test]
+fn yield_errors() {
+    let statuses = statuses(OnError::Yield);
+
+    assert_eq!(statuses.len(), 4);
+    assert!(matches!(statuses[0], Ok(native::Status::Pending)));
+    assert!(matches!(
+        statuses[1],
+        Err(ProstConvertError::UnknownEnumValue(0))
+    ));
+    assert!(matches!(
+        statuses[2],
+        Err(ProstConvertError::MissingRequiredField)
+    ));
+    assert!(matches!(statuses[3], Ok(native::Status::Shipped)));
+}
+
+#[test]
+fn skip_errors() {
+    let statuses = statuses(OnError::Skip);
+
+    // Only conversion errors are skipped.
+    assert_eq!(statuses.len(), 3);
+    assert!(matches!(statuses[0], Ok(native::Status::Pending)));
+    assert!(matches!(
+        statuses[1],
+        Err(ProstConvertError::MissingRequiredField)
+    ));
+    assert!(matches!(statuses[2], Ok(native::Status::Shipped)));
+}
+
+#[test]
+fn terminate_on_error() {
+    let statuses = statuses(OnError::Terminate);
+
+    assert_eq!(statuses.len(), 2);
+    assert!(matches!(statuses[0], Ok(native::Status::Pending)));
+    assert!(matches!(
+        statuses[1],
+        Err(ProstConvertError::UnknownEnumValue(0))
+    ));
+}
+
+#[test]
+fn default_yields_errors() {
+    let stream = stream::iter(requests()).try_into_native_stream::<native::ListOrdersRequest>();
+
+    assert_eq!(block_on(stream.collect::<Vec<_>>()).len(), 4);
+}
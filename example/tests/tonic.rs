@@ -0,0 +1,55 @@
+use prost_convert::tonic::{into_response, try_from_request};
+use prost_convert_example::{native::example::shop as native, proto::example::shop as proto};
+use tonic::{Code, Request, Status};
+
+fn proto_item() -> proto::Item {
+    proto::Item {
+        sku: "donut".to_owned(),
+        quantity: 12,
+    }
+}
+
+#[test]
+fn request_into_native() {
+    let mut request = Request::new(proto_item());
+    request
+        .metadata_mut()
+        .insert("x-request-id", "42".parse().unwrap());
+
+    let (metadata, item): (_, native::Item) = try_from_request(request).unwrap();
+
+    assert_eq!(metadata.get("x-request-id").unwrap(), "42");
+    assert_eq!(item.sku, "donut");
+    assert_eq!(item.quantity, 12);
+}
+
+#[test]
+fn response_from_native() {
+    let item = native::Item {
+        sku: "donut".to_owned(),
+        quantity: 12,
+    };
+
+    assert_eq!(
+        into_response::<_, proto::Item>(item).into_inner(),
+        proto_item()
+    );
+}
+
+#[test]
+fn error_into_invalid_argument() {
+    let order = proto::Order {
+        id: "not a uuid".to_owned(),
+        ..Default::default()
+    };
+    let status = try_from_request::<_, native::Order>(Request::new(order)).unwrap_err();
+
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(status.message().starts_with("invalid message: "));
+
+    let status = Status::from(prost_convert::ProstConvertError::MissingRequiredField);
+    assert_eq!(
+        status.message(),
+        "invalid message: prost struct miss a required field"
+    );
+}
@@ -0,0 +1,10 @@
+use prost_convert::ProstConvert;
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "prost_convert_example::proto::example::account::Address")]
+struct Address {
+    street: String,
+    zip_code: String,
+}
+
+fn main() {}
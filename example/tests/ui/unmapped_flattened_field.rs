@@ -0,0 +1,20 @@
+use prost_convert::ProstConvert;
+
+// `city` is neither mapped by `Address` nor by the struct flattened into it.
+#[derive(ProstConvert)]
+#[prost_convert(src = "prost_convert_example::proto::example::account::Address")]
+struct Address {
+    #[prost_convert(flatten)]
+    street: Street,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(
+    src = "prost_convert_example::proto::example::account::Address",
+    proto_default
+)]
+struct Street {
+    street: String,
+}
+
+fn main() {}
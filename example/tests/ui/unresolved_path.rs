@@ -0,0 +1,14 @@
+use prost_convert::ProstConvert;
+#[allow(unused_imports)]
+use prost_convert_example::proto::example::account as generated;
+
+// The manifest only knows the full paths of the generated structs, not the aliases of their
+// modules.
+#[derive(ProstConvert)]
+#[prost_convert(src = "generated::Address")]
+struct Address {
+    street: String,
+    city: String,
+}
+
+fn main() {}
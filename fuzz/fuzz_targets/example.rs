@@ -0,0 +1,20 @@
+//! Convert the messages of the example crate, generated by `prost-convert-build`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prost_convert::testing::fuzz_try_from_proto;
+use prost_convert_example::native::example::{account as native_account, shop as native_shop};
+use prost_convert_example::proto::example::{account, shop};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&target, data)) = data.split_first() else {
+        return;
+    };
+    match target % 4 {
+        0 => fuzz_try_from_proto::<account::User, native_account::User>(data),
+        1 => fuzz_try_from_proto::<shop::Order, native_shop::Order>(data),
+        2 => fuzz_try_from_proto::<shop::ListOrdersRequest, native_shop::ListOrdersRequest>(data),
+        _ => fuzz_try_from_proto::<shop::GetOrderRequest, native_shop::GetOrderRequest>(data),
+    }
+});
@@ -0,0 +1,197 @@
+//! Convert a message covering the conversions implemented in `prost-convert`.
+
+#![no_main]
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use indexmap::{IndexMap, IndexSet};
+use libfuzzer_sys::fuzz_target;
+use prost_convert::ProstConvert;
+use smallvec::SmallVec;
+use uuid::Uuid;
+
+mod proto {
+    use std::collections::HashMap;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Scalars {
+        #[prost(double, tag = "1")]
+        pub double: f64,
+        #[prost(float, tag = "2")]
+        pub float: f32,
+        #[prost(int32, tag = "3")]
+        pub int32: i32,
+        #[prost(int64, tag = "4")]
+        pub int64: i64,
+        #[prost(uint32, tag = "5")]
+        pub uint32: u32,
+        #[prost(uint64, tag = "6")]
+        pub uint64: u64,
+        #[prost(bool, tag = "7")]
+        pub boolean: bool,
+        #[prost(string, tag = "8")]
+        pub string: String,
+        #[prost(bytes = "vec", tag = "9")]
+        pub bytes: Vec<u8>,
+        #[prost(uint32, tag = "10")]
+        pub uint8: u32,
+        #[prost(uint32, tag = "11")]
+        pub uint16: u32,
+        #[prost(int32, tag = "12")]
+        pub int8: i32,
+        #[prost(int32, tag = "13")]
+        pub int16: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Parsed {
+        #[prost(string, tag = "1")]
+        pub path: String,
+        #[prost(string, tag = "2")]
+        pub ip: String,
+        #[prost(string, tag = "3")]
+        pub ipv4: String,
+        #[prost(string, tag = "4")]
+        pub ipv6: String,
+        #[prost(string, tag = "5")]
+        pub socket: String,
+        #[prost(string, tag = "6")]
+        pub uuid: String,
+        #[prost(string, tag = "7")]
+        pub name: String,
+        #[prost(uint32, tag = "8")]
+        pub percent: u32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WellKnown {
+        #[prost(message, optional, tag = "1")]
+        pub timestamp: Option<prost_types::Timestamp>,
+        #[prost(message, optional, tag = "2")]
+        pub duration: Option<prost_types::Duration>,
+        #[prost(message, optional, tag = "3")]
+        pub any: Option<prost_types::Any>,
+        #[prost(message, optional, tag = "4")]
+        pub value: Option<prost_types::Value>,
+        #[prost(message, optional, tag = "5")]
+        pub mask: Option<prost_types::FieldMask>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Collections {
+        #[prost(string, repeated, tag = "1")]
+        pub uuids: Vec<String>,
+        #[prost(uint32, repeated, tag = "2")]
+        pub deque: Vec<u32>,
+        #[prost(string, repeated, tag = "3")]
+        pub hash_set: Vec<String>,
+        #[prost(int32, repeated, tag = "4")]
+        pub btree_set: Vec<i32>,
+        #[prost(string, repeated, tag = "5")]
+        pub unique: Vec<String>,
+        #[prost(string, repeated, tag = "6")]
+        pub index_set: Vec<String>,
+        #[prost(uint32, repeated, tag = "7")]
+        pub small_vec: Vec<u32>,
+        #[prost(map = "string, message", tag = "8")]
+        pub children: HashMap<String, Tree>,
+        #[prost(map = "uint32, string", tag = "9")]
+        pub index_map: HashMap<u32, String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Tree {
+        #[prost(message, optional, boxed, tag = "1")]
+        pub left: Option<Box<Tree>>,
+        #[prost(message, optional, boxed, tag = "2")]
+        pub right: Option<Box<Tree>>,
+        #[prost(message, optional, tag = "3")]
+        pub scalars: Option<Scalars>,
+    }
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Scalars")]
+struct Scalars {
+    double: f64,
+    float: f32,
+    int32: i32,
+    int64: i64,
+    uint32: u32,
+    uint64: u64,
+    boolean: bool,
+    string: String,
+    bytes: Vec<u8>,
+    uint8: u8,
+    uint16: u16,
+    int8: i8,
+    int16: i16,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Parsed")]
+struct Parsed {
+    path: PathBuf,
+    ip: IpAddr,
+    ipv4: Ipv4Addr,
+    ipv6: Ipv6Addr,
+    socket: SocketAddr,
+    uuid: Uuid,
+    #[prost_convert(validate(min_len = 1, max_len = 16, pattern = "^[a-z]+$"))]
+    name: String,
+    #[prost_convert(validate(range = "0..=100"))]
+    percent: u32,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::WellKnown")]
+struct WellKnown {
+    timestamp: SystemTime,
+    duration: Duration,
+    any: Option<prost_types::Any>,
+    value: Option<prost_types::Value>,
+    mask: Option<prost_types::FieldMask>,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Collections")]
+struct Collections {
+    uuids: Vec<Uuid>,
+    deque: VecDeque<u8>,
+    hash_set: HashSet<IpAddr>,
+    btree_set: BTreeSet<i16>,
+    #[prost_convert(duplicates = "reject")]
+    unique: BTreeSet<String>,
+    #[prost_convert(duplicates = "reject")]
+    index_set: IndexSet<String>,
+    #[prost_convert(validate(max_items = 8))]
+    small_vec: SmallVec<[u16; 4]>,
+    children: HashMap<String, Tree>,
+    index_map: IndexMap<u32, Ipv4Addr>,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Tree")]
+struct Tree {
+    left: Option<Box<Tree>>,
+    right: Option<Box<Tree>>,
+    scalars: Option<Scalars>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    use prost_convert::testing::fuzz_try_from_proto;
+
+    let Some((&target, data)) = data.split_first() else {
+        return;
+    };
+    match target % 5 {
+        0 => fuzz_try_from_proto::<proto::Scalars, Scalars>(data),
+        1 => fuzz_try_from_proto::<proto::Parsed, Parsed>(data),
+        2 => fuzz_try_from_proto::<proto::WellKnown, WellKnown>(data),
+        3 => fuzz_try_from_proto::<proto::Collections, Collections>(data),
+        _ => fuzz_try_from_proto::<proto::Tree, Tree>(data),
+    }
+});
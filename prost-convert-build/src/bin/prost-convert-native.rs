@@ -0,0 +1,47 @@
+//! Generate the native types of a file descriptor set.
+//!
+//! ```text
+//! protoc --include_imports --descriptor_set_out=shop.bin shop.proto
+//! prost-convert-native shop.bin --config native.toml --output src/native.rs
+//! ```
+
+use std::{error::Error, fs, io::Write, path::PathBuf};
+
+use prost::Message;
+use prost_convert_build::NativeConfig;
+use prost_types::FileDescriptorSet;
+
+const USAGE: &str =
+    "usage: prost-convert-native <DESCRIPTOR_SET> [--config <FILE>] [--output <FILE>]";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut descriptor_set = None;
+    let mut config = None;
+    let mut output = None;
+
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--config") => config = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            Some("--output") => output = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            Some("-h" | "--help") => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if descriptor_set.is_none() => descriptor_set = Some(PathBuf::from(arg)),
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let fds = FileDescriptorSet::decode(fs::read(descriptor_set.ok_or(USAGE)?)?.as_slice())?;
+    let config = match config {
+        Some(path) => NativeConfig::from_toml(&fs::read_to_string(path)?)?,
+        None => NativeConfig::default(),
+    };
+    let native = config.generate(&fds)?;
+    match output {
+        Some(path) => fs::write(path, native)?,
+        None => std::io::stdout().write_all(native.as_bytes())?,
+    }
+    Ok(())
+}
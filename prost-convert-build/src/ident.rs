@@ -0,0 +1,56 @@
+//! Rust identifiers of the code generated by prost.
+//!
+//! These mirror the rules of `prost-build`, which doesn't expose them.
+
+use heck::{ToSnakeCase, ToUpperCamelCase};
+
+fn sanitize_identifier(ident: String) -> String {
+    match ident.as_str() {
+        "as" | "break" | "const" | "continue" | "else" | "enum" | "false" | "fn" | "for" | "if"
+        | "impl" | "in" | "let" | "loop" | "match" | "mod" | "move" | "mut" | "pub" | "ref"
+        | "return" | "static" | "struct" | "trait" | "true" | "type" | "unsafe" | "use"
+        | "where" | "while" | "dyn" | "abstract" | "become" | "box" | "do" | "final" | "macro"
+        | "override" | "priv" | "typeof" | "unsized" | "virtual" | "yield" | "async" | "await"
+        | "try" => format!("r#{ident}"),
+        "_" | "super" | "self" | "Self" | "extern" | "crate" => format!("{ident}_"),
+        s if s.starts_with(|c: char| c.is_numeric()) => format!("_{ident}"),
+        _ => ident,
+    }
+}
+
+/// Return the name of the field or module generated for a proto name.
+pub fn to_snake(name: &str) -> String {
+    sanitize_identifier(name.to_snake_case())
+}
+
+/// Return the name of the type generated for a proto name.
+pub fn to_upper_camel(name: &str) -> String {
+    sanitize_identifier(name.to_upper_camel_case())
+}
+
+/// Return the name of the variant generated for an enum value, without the name of the enum.
+pub fn strip_enum_prefix(prefix: &str, name: &str) -> String {
+    let stripped = name.strip_prefix(prefix).unwrap_or(name);
+    // "Foo" is not a prefix of "Foobar".
+    let stripped = if stripped.starts_with(char::is_uppercase) {
+        stripped
+    } else {
+        name
+    };
+    sanitize_identifier(stripped.to_owned())
+}
+
+/// Return the prefix of the proto names of a package, and the path of its module.
+pub fn package_prefixes(package: &str) -> (String, Vec<String>) {
+    let proto_prefix = if package.is_empty() {
+        String::new()
+    } else {
+        format!(".{package}")
+    };
+    let rust_prefix = package
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(to_snake)
+        .collect();
+    (proto_prefix, rust_prefix)
+}
@@ -0,0 +1,83 @@
+//! Build script helpers for `prost-convert`.
+//!
+//! `#[derive(ProstConvert)]` only sees the path of the proto struct, so it can't know how prost
+//! generated its fields. This crate compiles the proto files with `prost-build` and writes a
+//! manifest of the generated fields, that the derive macro reads at compile time.
+//!
+//! ```rust,no_run
+//! // build.rs
+//! fn main() -> std::io::Result<()> {
+//!     prost_convert_build::compile_protos(&["proto/user.proto"], &["proto"])
+//! }
+//! ```
+
+#![deny(clippy::all, missing_docs)]
+
+mod ident;
+mod manifest;
+mod native;
+
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+};
+
+pub use manifest::{Field, Manifest, Message, Presence};
+pub use native::NativeConfig;
+pub use prost_build::Config;
+use prost_types::FileDescriptorSet;
+
+/// The environment variable holding the path of the manifest read by the derive macro.
+pub const MANIFEST_ENV: &str = "PROST_CONVERT_MANIFEST";
+
+/// Compile the proto files with the default `prost_build::Config`, and emit the manifest.
+pub fn compile_protos(
+    protos: &[impl AsRef<Path>],
+    includes: &[impl AsRef<Path>],
+) -> io::Result<()> {
+    compile_with_config(Config::new(), protos, includes)
+}
+
+/// Compile the proto files with `config`, and emit the manifest.
+pub fn compile_with_config(
+    mut config: Config,
+    protos: &[impl AsRef<Path>],
+    includes: &[impl AsRef<Path>],
+) -> io::Result<()> {
+    let fds = config.load_fds(protos, includes)?;
+    emit_manifest(&fds)?;
+    config.compile_fds(fds)
+}
+
+/// Write the manifest of `fds` in `OUT_DIR`, and tell cargo to expose its path to the derive
+/// macro.
+///
+/// This is meant for build scripts which already have a file descriptor set. Only one manifest
+/// can be used by a crate, so every proto file must be in `fds`. The code generated by prost must
+/// be included in `crate::proto`, see [`emit_manifest_in`] otherwise.
+pub fn emit_manifest(fds: &FileDescriptorSet) -> io::Result<()> {
+    emit_manifest_in(fds, "crate::proto")
+}
+
+/// Same as [`emit_manifest`], for code generated by prost included in the module `proto_path`,
+/// with one module per package.
+pub fn emit_manifest_in(fds: &FileDescriptorSet, proto_path: &str) -> io::Result<()> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR environment variable is not set",
+        )
+    })?;
+    let path = PathBuf::from(out_dir).join("prost-convert-manifest.txt");
+    let manifest = Manifest {
+        proto_path: proto_path.to_owned(),
+        // The name of the library crate, unless renamed in its manifest.
+        crate_name: env::var("CARGO_PKG_NAME")
+            .ok()
+            .map(|name| name.replace('-', "_")),
+        ..Manifest::from_file_descriptor_set(fds)
+    };
+    manifest.write(&path)?;
+    println!("cargo:rustc-env={}={}", MANIFEST_ENV, path.display());
+    Ok(())
+}
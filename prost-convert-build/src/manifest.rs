@@ -0,0 +1,206 @@
+use std::{fmt, fs, io, path::Path};
+
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto, FileDescriptorSet,
+};
+
+use crate::ident::{package_prefixes, to_snake, to_upper_camel};
+
+/// The fields of the structs generated by prost.
+///
+/// It is written by the build script and read by the derive macro, which can't see the proto
+/// structs by itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// Path of the module in which the code generated by prost is included, with one module per
+    /// package, for instance `crate::proto`.
+    pub proto_path: String,
+    /// Name of the library crate, by which its integration tests name `proto_path`.
+    pub crate_name: Option<String>,
+    /// Every message of the file descriptor set.
+    pub messages: Vec<Message>,
+}
+
+/// A message, and the struct generated for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Fully qualified proto name, for instance `.foo.bar.Baz`.
+    pub proto_name: String,
+    /// Path of the generated struct from the module of the package root, for instance
+    /// `foo::bar::Baz`.
+    pub rust_path: String,
+    /// The fields of the generated struct.
+    pub fields: Vec<Field>,
+}
+
+/// A field of a generated struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// Name of the struct field, without the `r#` prefix of raw identifiers.
+    pub name: String,
+    /// How the value is wrapped in the struct field.
+    pub presence: Presence,
+}
+
+/// How prost wraps the value of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    /// The value itself, the zero value standing for an unset field.
+    Plain,
+    /// An `Option`: messages, `optional` scalars and oneofs.
+    Optional,
+    /// A `Vec`.
+    Repeated,
+    /// A map.
+    Map,
+}
+
+impl Presence {
+    fn as_str(self) -> &'static str {
+        match self {
+            Presence::Plain => "plain",
+            Presence::Optional => "optional",
+            Presence::Repeated => "repeated",
+            Presence::Map => "map",
+        }
+    }
+}
+
+impl Manifest {
+    /// Describe the structs prost generates for `fds`, included in `crate::proto`.
+    pub fn from_file_descriptor_set(fds: &FileDescriptorSet) -> Self {
+        let mut messages = Vec::new();
+        for file in &fds.file {
+            let proto3 = file.syntax() == "proto3";
+            let package = file.package();
+            let (proto_prefix, rust_prefix) = package_prefixes(package);
+            for message in &file.message_type {
+                add_message(&mut messages, message, &proto_prefix, &rust_prefix, proto3);
+            }
+        }
+        Self {
+            proto_path: "crate::proto".to_owned(),
+            crate_name: None,
+            messages,
+        }
+    }
+
+    /// Write the manifest to `path`.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+fn add_message(
+    messages: &mut Vec<Message>,
+    message: &DescriptorProto,
+    proto_prefix: &str,
+    rust_prefix: &[String],
+    proto3: bool,
+) {
+    let proto_name = format!("{proto_prefix}.{}", message.name());
+    let mut rust_path = rust_prefix.to_vec();
+    rust_path.push(to_upper_camel(message.name()));
+
+    let mut fields = Vec::new();
+    for field in &message.field {
+        // Fields of a oneof are variants of the enum of the oneof.
+        if field.oneof_index.is_some() && !field.proto3_optional() {
+            continue;
+        }
+        fields.push(Field {
+            name: field_name(field.name()),
+            presence: presence(field, message, &proto_name, proto3),
+        });
+    }
+    for (index, oneof) in message.oneof_decl.iter().enumerate() {
+        // `optional` fields of proto3 are wrapped in a synthetic oneof.
+        let synthetic = message
+            .field
+            .iter()
+            .any(|field| field.oneof_index == Some(index as i32) && field.proto3_optional());
+        if !synthetic {
+            fields.push(Field {
+                name: field_name(oneof.name()),
+                presence: Presence::Optional,
+            });
+        }
+    }
+    messages.push(Message {
+        proto_name: proto_name.clone(),
+        rust_path: rust_path.join("::"),
+        fields,
+    });
+
+    let mut rust_prefix = rust_prefix.to_vec();
+    rust_prefix.push(to_snake(message.name()));
+    for nested in &message.nested_type {
+        if !is_map_entry(nested) {
+            add_message(messages, nested, &proto_name, &rust_prefix, proto3);
+        }
+    }
+}
+
+fn field_name(name: &str) -> String {
+    to_snake(name).trim_start_matches("r#").to_owned()
+}
+
+fn is_map_entry(message: &DescriptorProto) -> bool {
+    message
+        .options
+        .as_ref()
+        .is_some_and(|options| options.map_entry())
+}
+
+// Same rules as `prost-build`.
+fn presence(
+    field: &FieldDescriptorProto,
+    message: &DescriptorProto,
+    proto_name: &str,
+    proto3: bool,
+) -> Presence {
+    if field.label() == Label::Repeated {
+        let map = field.r#type() == Type::Message
+            && message.nested_type.iter().any(|nested| {
+                is_map_entry(nested)
+                    && field.type_name() == format!("{proto_name}.{}", nested.name())
+            });
+        return if map {
+            Presence::Map
+        } else {
+            Presence::Repeated
+        };
+    }
+    if field.proto3_optional() {
+        return Presence::Optional;
+    }
+    if field.label() != Label::Optional {
+        return Presence::Plain;
+    }
+    match field.r#type() {
+        Type::Message => Presence::Optional,
+        _ if proto3 => Presence::Plain,
+        _ => Presence::Optional,
+    }
+}
+
+impl fmt::Display for Manifest {
+    /// The `proto_path <path>` and `crate <name>` lines, then one
+    /// `message <proto name> <rust path>` line per message, followed by one
+    /// `field <name> <presence>` line per field.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Generated by prost-convert-build.")?;
+        writeln!(f, "proto_path {}", self.proto_path)?;
+        if let Some(crate_name) = &self.crate_name {
+            writeln!(f, "crate {crate_name}")?;
+        }
+        for message in &self.messages {
+            writeln!(f, "message {} {}", message.proto_name, message.rust_path)?;
+            for field in &message.fields {
+                writeln!(f, "field {} {}", field.name, field.presence.as_str())?;
+            }
+        }
+        Ok(())
+    }
+}
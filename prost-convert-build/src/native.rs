@@ -0,0 +1,674 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io,
+};
+
+use proc_macro2::{Span, TokenStream};
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet,
+    ServiceDescriptorProto,
+};
+use quote::quote;
+use serde::Deserialize;
+
+use crate::ident::{package_prefixes, strip_enum_prefix, to_snake, to_upper_camel};
+
+/// Configuration of the native types generator, usually read from a TOML file.
+///
+/// ```toml
+/// proto_path = "crate::proto"
+/// native_path = "crate::native"
+///
+/// [substitutions]
+/// ip = "std::net::IpAddr"
+/// ".acme.shop.Order.id" = "uuid::Uuid"
+/// ```
+///
+/// With `services = true`, a native trait is also generated for every service, along with an
+/// adapter implementing the server trait generated by `tonic-build`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NativeConfig {
+    /// Path of the module holding the code generated by prost, with one module per package.
+    #[serde(default = "default_proto_path")]
+    pub proto_path: String,
+    /// Path of the module in which the native types are included.
+    #[serde(default = "default_native_path")]
+    pub native_path: String,
+    /// Native types replacing the type of a field, by field name or by fully qualified field name.
+    /// The type of a repeated field is the type of its elements, and the type of a map field is
+    /// the type of its values.
+    #[serde(default)]
+    pub substitutions: BTreeMap<String, String>,
+    /// Whether to generate the native services. The server code of `tonic-build` must be in the
+    /// module of the package in `proto_path`, and `prost-convert` must have the `tonic` feature.
+    #[serde(default)]
+    pub services: bool,
+}
+
+fn default_proto_path() -> String {
+    "crate::proto".to_owned()
+}
+
+fn default_native_path() -> String {
+    "crate::native".to_owned()
+}
+
+impl Default for NativeConfig {
+    fn default() -> Self {
+        Self {
+            proto_path: default_proto_path(),
+            native_path: default_native_path(),
+            substitutions: BTreeMap::new(),
+            services: false,
+        }
+    }
+}
+
+impl NativeConfig {
+    /// Read the configuration from TOML.
+    pub fn from_toml(config: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(config)
+    }
+
+    /// Return the source code of the native types of every message and enum of `fds`.
+    ///
+    /// - Oneofs become required native enums.
+    /// - Wrapper types become `Option`s, `Timestamp` becomes `SystemTime` and `Duration`
+    ///   becomes `std::time::Duration`.
+    /// - `*_UNSPECIFIED` enum variants are dropped.
+    /// - Every type is the canonical native type of its proto type, see `ProstProto`.
+    /// - A service `Foo` becomes a native trait `Foo`, whose methods take and return native
+    ///   messages, and a `FooAdapter` implementing the tonic trait for any native implementation.
+    ///
+    /// The `google.protobuf` package is not generated.
+    pub fn generate(&self, fds: &FileDescriptorSet) -> io::Result<String> {
+        let generator = Generator::new(self, fds)?;
+        let mut root = Module::default();
+        for file in &fds.file {
+            let package = file.package();
+            if package == "google.protobuf" {
+                continue;
+            }
+            let proto3 = file.syntax() == "proto3";
+            let (proto_prefix, rust_prefix) = package_prefixes(package);
+            for message in &file.message_type {
+                generator.message(&mut root, message, &proto_prefix, &rust_prefix, proto3)?;
+            }
+            for enumeration in &file.enum_type {
+                let item = generator.enumeration(enumeration, &rust_prefix);
+                root.get(&rust_prefix).items.push(item);
+            }
+            if self.services {
+                for service in &file.service {
+                    let item = generator.service(service, &proto_prefix, &rust_prefix)?;
+                    root.get(&rust_prefix).items.push(item);
+                }
+            }
+        }
+        let file = syn::parse2(root.into_tokens()).map_err(invalid_input)?;
+        Ok(prettyplease::unparse(&file))
+    }
+}
+
+fn invalid_input(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, err.to_string())
+}
+
+fn parse<T: syn::parse::Parse>(value: &str, what: &str) -> io::Result<T> {
+    syn::parse_str(value).map_err(|err| invalid_input(format!("invalid {what} `{value}`: {err}")))
+}
+
+fn ident(name: &str) -> syn::Ident {
+    match name.strip_prefix("r#") {
+        Some(raw) => syn::Ident::new_raw(raw, Span::call_site()),
+        None => syn::Ident::new(name, Span::call_site()),
+    }
+}
+
+/// A module of the generated code.
+#[derive(Default)]
+struct Module {
+    items: Vec<TokenStream>,
+    modules: BTreeMap<String, Module>,
+}
+
+impl Module {
+    fn get(&mut self, path: &[String]) -> &mut Module {
+        path.iter().fold(self, |module, name| {
+            module.modules.entry(name.clone()).or_default()
+        })
+    }
+
+    fn into_tokens(self) -> TokenStream {
+        let items = self.items;
+        let modules = self.modules.into_iter().map(|(name, module)| {
+            let name = ident(&name);
+            let content = module.into_tokens();
+            quote!(pub mod #name { #content })
+        });
+        quote!(#(#items)* #(#modules)*)
+    }
+}
+
+struct Generator<'a> {
+    config: &'a NativeConfig,
+    proto_path: syn::Path,
+    native_path: syn::Path,
+    /// The path from the package root of every message and enum.
+    types: HashMap<String, Vec<String>>,
+    /// The messages held by the non-repeated message fields of every message.
+    graph: HashMap<String, Vec<String>>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(config: &'a NativeConfig, fds: &FileDescriptorSet) -> io::Result<Self> {
+        let mut generator = Self {
+            config,
+            proto_path: parse(&config.proto_path, "path")?,
+            native_path: parse(&config.native_path, "path")?,
+            types: HashMap::new(),
+            graph: HashMap::new(),
+        };
+        for file in &fds.file {
+            let package = file.package();
+            let (proto_prefix, rust_prefix) = package_prefixes(package);
+            for message in &file.message_type {
+                generator.index_message(message, &proto_prefix, &rust_prefix);
+            }
+            for enumeration in &file.enum_type {
+                generator.index_enum(enumeration, &proto_prefix, &rust_prefix);
+            }
+        }
+        Ok(generator)
+    }
+
+    fn index_message(
+        &mut self,
+        message: &DescriptorProto,
+        proto_prefix: &str,
+        rust_prefix: &[String],
+    ) {
+        let proto_name = format!("{proto_prefix}.{}", message.name());
+        let mut path = rust_prefix.to_vec();
+        path.push(to_upper_camel(message.name()));
+        self.types.insert(proto_name.clone(), path);
+        self.graph.insert(
+            proto_name.clone(),
+            message
+                .field
+                .iter()
+                .filter(|field| field.r#type() == Type::Message && field.label() != Label::Repeated)
+                .map(|field| field.type_name().to_owned())
+                .collect(),
+        );
+
+        let mut rust_prefix = rust_prefix.to_vec();
+        rust_prefix.push(to_snake(message.name()));
+        for nested in &message.nested_type {
+            self.index_message(nested, &proto_name, &rust_prefix);
+        }
+        for enumeration in &message.enum_type {
+            self.index_enum(enumeration, &proto_name, &rust_prefix);
+        }
+    }
+
+    fn index_enum(
+        &mut self,
+        enumeration: &EnumDescriptorProto,
+        proto_prefix: &str,
+        rust_prefix: &[String],
+    ) {
+        let mut path = rust_prefix.to_vec();
+        path.push(to_upper_camel(enumeration.name()));
+        self.types
+            .insert(format!("{proto_prefix}.{}", enumeration.name()), path);
+    }
+
+    /// Return `true` if `inner` holds `outer` through non-repeated message fields. Prost boxes such
+    /// fields, and so must we.
+    fn is_nested(&self, inner: &str, outer: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![inner];
+        while let Some(message) = stack.pop() {
+            if message == outer {
+                return true;
+            }
+            if visited.insert(message) {
+                stack.extend(
+                    self.graph
+                        .get(message)
+                        .into_iter()
+                        .flatten()
+                        .map(String::as_str),
+                );
+            }
+        }
+        false
+    }
+
+    fn path(&self, root: &syn::Path, segments: &[String]) -> TokenStream {
+        let segments = segments.iter().map(|segment| ident(segment));
+        quote!(#root #(:: #segments)*)
+    }
+
+    fn src(&self, segments: &[String]) -> String {
+        self.path(&self.proto_path, segments)
+            .to_string()
+            .replace(' ', "")
+    }
+
+    fn message(
+        &self,
+        module: &mut Module,
+        message: &DescriptorProto,
+        proto_prefix: &str,
+        rust_prefix: &[String],
+        proto3: bool,
+    ) -> io::Result<()> {
+        let proto_name = format!("{proto_prefix}.{}", message.name());
+        let name = ident(&to_upper_camel(message.name()));
+        let mut path = rust_prefix.to_vec();
+        path.push(to_upper_camel(message.name()));
+        let src = self.src(&path);
+        let doc = format!(" Native type of `{proto_name}`.");
+
+        let mut nested_prefix = rust_prefix.to_vec();
+        nested_prefix.push(to_snake(message.name()));
+
+        let mut fields = Vec::new();
+        for field in &message.field {
+            // Fields of a oneof are variants of the enum of the oneof.
+            if field.oneof_index.is_some() && !field.proto3_optional() {
+                continue;
+            }
+            let field_name = ident(&to_snake(field.name()));
+            let ty = self.field_type(field, message, &proto_name, proto3)?;
+            fields.push(quote!(pub #field_name: #ty));
+        }
+
+        for (index, oneof) in message.oneof_decl.iter().enumerate() {
+            let variants = message
+                .field
+                .iter()
+                .filter(|field| field.oneof_index == Some(index as i32))
+                .collect::<Vec<_>>();
+            // `optional` fields of proto3 are wrapped in a synthetic oneof.
+            if variants.iter().any(|field| field.proto3_optional()) {
+                continue;
+            }
+            let oneof_name = to_upper_camel(oneof.name());
+            let mut oneof_path = nested_prefix.clone();
+            oneof_path.push(oneof_name.clone());
+            let ty = self.path(&self.native_path, &oneof_path);
+            let field_name = ident(&to_snake(oneof.name()));
+            fields.push(quote!(pub #field_name: #ty));
+
+            let oneof_src = self.src(&oneof_path);
+            let oneof_doc = format!(
+                " Native type of the `{}` oneof of `{proto_name}`.",
+                oneof.name()
+            );
+            let oneof_name = ident(&oneof_name);
+            let variants = variants
+                .iter()
+                .map(|field| {
+                    let variant = ident(&to_upper_camel(field.name()));
+                    let mut ty = self.element_type(field, &proto_name)?;
+                    if self.boxed(field, &proto_name) {
+                        ty = quote!(Box<#ty>);
+                    }
+                    Ok(quote!(#variant(#ty)))
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            module.get(&nested_prefix).items.push(quote!(
+                #[doc = #oneof_doc]
+                #[derive(Debug, Clone, PartialEq, prost_convert::ProstConvert)]
+                #[prost_convert(src = #oneof_src, canonical)]
+                pub enum #oneof_name {
+                    #(#variants,)*
+                }
+            ));
+        }
+
+        module.get(rust_prefix).items.push(quote!(
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq, prost_convert::ProstConvert)]
+            #[prost_convert(src = #src, canonical)]
+            pub struct #name {
+                #(#fields,)*
+            }
+        ));
+
+        for nested in &message.nested_type {
+            if !is_map_entry(nested) {
+                self.message(module, nested, &proto_name, &nested_prefix, proto3)?;
+            }
+        }
+        for enumeration in &message.enum_type {
+            let item = self.enumeration(enumeration, &nested_prefix);
+            module.get(&nested_prefix).items.push(item);
+        }
+        Ok(())
+    }
+
+    fn enumeration(
+        &self,
+        enumeration: &EnumDescriptorProto,
+        rust_prefix: &[String],
+    ) -> TokenStream {
+        let enum_name = to_upper_camel(enumeration.name());
+        let mut path = rust_prefix.to_vec();
+        path.push(enum_name.clone());
+        let src = self.src(&path);
+        let doc = format!(" Native type of the `{}` enum.", enumeration.name());
+
+        let mut numbers = HashSet::new();
+        let mut unmapped = false;
+        let mut variants = Vec::new();
+        for value in &enumeration.value {
+            // Prost only generates the first of the aliases of a value.
+            if !numbers.insert(value.number()) {
+                continue;
+            }
+            if value.name() == "UNSPECIFIED" || value.name().ends_with("_UNSPECIFIED") {
+                unmapped = true;
+                continue;
+            }
+            variants.push(ident(&strip_enum_prefix(
+                &enum_name,
+                &to_upper_camel(value.name()),
+            )));
+        }
+        let enum_name = ident(&enum_name);
+        let attrs = if unmapped {
+            quote!(src = #src, canonical, allow_unmapped)
+        } else {
+            quote!(src = #src, canonical)
+        };
+
+        quote!(
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, prost_convert::ProstConvert)]
+            #[prost_convert(#attrs)]
+            pub enum #enum_name {
+                #(#variants,)*
+            }
+        )
+    }
+
+    fn service(
+        &self,
+        service: &ServiceDescriptorProto,
+        proto_prefix: &str,
+        rust_prefix: &[String],
+    ) -> io::Result<TokenStream> {
+        let proto_name = format!("{proto_prefix}.{}", service.name());
+        let service_name = to_upper_camel(service.name());
+        let mut server_path = rust_prefix.to_vec();
+        server_path.push(format!("{}_server", to_snake(&service_name)));
+        server_path.push(service_name.clone());
+        let server = self.path(&self.proto_path, &server_path);
+        let adapter = ident(&format!("{service_name}Adapter"));
+        let service_name = ident(&service_name);
+        let doc = format!(" Native version of the `{proto_name}` service.");
+        let adapter_doc = format!(
+            " Implement the tonic server trait of `{proto_name}` with a native implementation."
+        );
+
+        let mut native_items = Vec::new();
+        let mut adapter_items = Vec::new();
+        for method in &service.method {
+            let method_name = ident(&to_snake(method.name()));
+            let input = self.message_type(method.input_type(), false)?;
+            let output = self.message_type(method.output_type(), false)?;
+            let proto_input = self.message_type(method.input_type(), true)?;
+            let proto_output = self.message_type(method.output_type(), true)?;
+
+            // Tonic names the response stream after the method.
+            let stream = ident(&format!("{}Stream", method.name()));
+            let (input, request) = if method.client_streaming() {
+                (
+                    quote!(prost_convert::tonic::BoxStream<#input>),
+                    quote!(prost_convert::tonic::native_stream_request(request)),
+                )
+            } else {
+                (
+                    input,
+                    quote!(prost_convert::tonic::native_request(request)?),
+                )
+            };
+            let proto_input = if method.client_streaming() {
+                quote!(tonic::Streaming<#proto_input>)
+            } else {
+                proto_input
+            };
+            let (output, proto_output, response) = if method.server_streaming() {
+                let stream_doc = format!(" The stream of `{}` responses.", method.name());
+                native_items.push(quote!(
+                    #[doc = #stream_doc]
+                    type #stream: prost_convert::tonic::Stream<
+                            Item = std::result::Result<#output, tonic::Status>,
+                        > + Send
+                        + 'static;
+                ));
+                adapter_items.push(quote!(
+                    type #stream = prost_convert::tonic::ProtoStream<T::#stream, #proto_output>;
+                ));
+                (
+                    quote!(Self::#stream),
+                    quote!(Self::#stream),
+                    quote!(prost_convert::tonic::proto_stream_response(response)),
+                )
+            } else {
+                (
+                    output,
+                    proto_output,
+                    quote!(prost_convert::tonic::proto_response(response)),
+                )
+            };
+
+            native_items.push(quote!(
+                async fn #method_name(
+                    &self,
+                    request: tonic::Request<#input>,
+                ) -> std::result::Result<tonic::Response<#output>, tonic::Status>;
+            ));
+            adapter_items.push(quote!(
+                async fn #method_name(
+                    &self,
+                    request: tonic::Request<#proto_input>,
+                ) -> std::result::Result<tonic::Response<#proto_output>, tonic::Status> {
+                    let request = #request;
+                    let response = #service_name::#method_name(&self.0, request).await?;
+                    std::result::Result::Ok(#response)
+                }
+            ));
+        }
+
+        Ok(quote!(
+            #[doc = #doc]
+            #[prost_convert::tonic::async_trait]
+            pub trait #service_name: Send + Sync + 'static {
+                #(#native_items)*
+            }
+
+            #[doc = #adapter_doc]
+            #[derive(Debug, Clone, Default)]
+            pub struct #adapter<T>(pub T);
+
+            #[prost_convert::tonic::async_trait]
+            impl<T: #service_name> #server for #adapter<T> {
+                #(#adapter_items)*
+            }
+        ))
+    }
+
+    /// Return the type of the input or output message of a method, either native or generated
+    /// by prost.
+    fn message_type(&self, type_name: &str, proto: bool) -> io::Result<TokenStream> {
+        if type_name.starts_with(".google.protobuf.") {
+            return Ok(match well_known_type(type_name) {
+                // Prost generates the wrappers as scalars and `Empty` as `()`.
+                Some(ty) if !proto || is_wrapper(type_name) => ty,
+                _ => {
+                    let name = ident(type_name.trim_start_matches(".google.protobuf."));
+                    quote!(prost_types::#name)
+                }
+            });
+        }
+        match self.types.get(type_name) {
+            Some(path) if proto => Ok(self.path(&self.proto_path, path)),
+            Some(path) => Ok(self.path(&self.native_path, path)),
+            None => Err(invalid_input(format!("unknown message `{type_name}`"))),
+        }
+    }
+
+    fn boxed(&self, field: &FieldDescriptorProto, proto_name: &str) -> bool {
+        field.label() != Label::Repeated
+            && field.r#type() == Type::Message
+            && self.is_nested(field.type_name(), proto_name)
+    }
+
+    /// Return the native type of a struct field.
+    fn field_type(
+        &self,
+        field: &FieldDescriptorProto,
+        message: &DescriptorProto,
+        proto_name: &str,
+        proto3: bool,
+    ) -> io::Result<TokenStream> {
+        let ty = self.element_type(field, proto_name)?;
+        if field.label() == Label::Repeated {
+            let entry = message.nested_type.iter().find(|nested| {
+                is_map_entry(nested)
+                    && field.type_name() == format!("{proto_name}.{}", nested.name())
+            });
+            return Ok(match entry {
+                Some(entry) => {
+                    let key = self.scalar_type(&entry.field[0]);
+                    let value = self
+                        .substitution(field, proto_name)?
+                        .map_or_else(|| self.element_type(&entry.field[1], proto_name), Ok)?;
+                    quote!(std::collections::HashMap<#key, #value>)
+                }
+                None => quote!(Vec<#ty>),
+            });
+        }
+        if self.boxed(field, proto_name) {
+            return Ok(quote!(Option<Box<#ty>>));
+        }
+        // Like prost, optional scalars of proto2 are wrapped in an `Option`.
+        let optional = field.proto3_optional()
+            || (field.label() == Label::Optional
+                && field.r#type() != Type::Message
+                && field.r#type() != Type::Group
+                && !proto3)
+            || (field.r#type() == Type::Message && is_wrapper(field.type_name()));
+        Ok(if optional { quote!(Option<#ty>) } else { ty })
+    }
+
+    /// Return the native type of a field value, ignoring whether it is repeated or optional.
+    fn element_type(
+        &self,
+        field: &FieldDescriptorProto,
+        proto_name: &str,
+    ) -> io::Result<TokenStream> {
+        if let Some(ty) = self.substitution(field, proto_name)? {
+            return Ok(ty);
+        }
+        Ok(match field.r#type() {
+            Type::Message | Type::Group | Type::Enum => {
+                let type_name = field.type_name();
+                if let Some(ty) = well_known_type(type_name) {
+                    return Ok(ty);
+                }
+                match self.types.get(type_name) {
+                    Some(path) => self.path(&self.native_path, path),
+                    None => {
+                        return Err(invalid_input(format!(
+                            "unknown type `{type_name}` of field `{proto_name}.{}`",
+                            field.name()
+                        )))
+                    }
+                }
+            }
+            _ => self.scalar_type(field),
+        })
+    }
+
+    fn substitution(
+        &self,
+        field: &FieldDescriptorProto,
+        proto_name: &str,
+    ) -> io::Result<Option<TokenStream>> {
+        let qualified = format!("{proto_name}.{}", field.name());
+        self.config
+            .substitutions
+            .get(&qualified)
+            .or_else(|| self.config.substitutions.get(field.name()))
+            .map(|ty| parse::<syn::Type>(ty, "type").map(|ty| quote!(#ty)))
+            .transpose()
+    }
+
+    fn scalar_type(&self, field: &FieldDescriptorProto) -> TokenStream {
+        match field.r#type() {
+            Type::Double => quote!(f64),
+            Type::Float => quote!(f32),
+            Type::Int32 | Type::Sint32 | Type::Sfixed32 | Type::Enum => quote!(i32),
+            Type::Int64 | Type::Sint64 | Type::Sfixed64 => quote!(i64),
+            Type::Uint32 | Type::Fixed32 => quote!(u32),
+            Type::Uint64 | Type::Fixed64 => quote!(u64),
+            Type::Bool => quote!(bool),
+            Type::String => quote!(String),
+            Type::Bytes | Type::Message | Type::Group => quote!(Vec<u8>),
+        }
+    }
+}
+
+fn is_map_entry(message: &DescriptorProto) -> bool {
+    message
+        .options
+        .as_ref()
+        .is_some_and(|options| options.map_entry())
+}
+
+fn is_wrapper(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        ".google.protobuf.BoolValue"
+            | ".google.protobuf.BytesValue"
+            | ".google.protobuf.DoubleValue"
+            | ".google.protobuf.FloatValue"
+            | ".google.protobuf.Int32Value"
+            | ".google.protobuf.Int64Value"
+            | ".google.protobuf.UInt32Value"
+            | ".google.protobuf.UInt64Value"
+            | ".google.protobuf.StringValue"
+            | ".google.protobuf.Empty"
+    )
+}
+
+/// Return the native type of the `google.protobuf` types, prost maps the wrapper types to scalars.
+fn well_known_type(type_name: &str) -> Option<TokenStream> {
+    Some(match type_name.strip_prefix(".google.protobuf.")? {
+        "BoolValue" => quote!(bool),
+        "BytesValue" => quote!(Vec<u8>),
+        "DoubleValue" => quote!(f64),
+        "FloatValue" => quote!(f32),
+        "Int32Value" => quote!(i32),
+        "Int64Value" => quote!(i64),
+        "UInt32Value" => quote!(u32),
+        "UInt64Value" => quote!(u64),
+        "StringValue" => quote!(String),
+        "Empty" => quote!(()),
+        "Timestamp" => quote!(std::time::SystemTime),
+        "Duration" => quote!(std::time::Duration),
+        "NullValue" => quote!(i32),
+        other => {
+            let ty = ident(other);
+            quote!(prost_types::#ty)
+        }
+    })
+}
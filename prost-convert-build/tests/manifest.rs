@@ -0,0 +1,143 @@
+use prost_convert_build::{Manifest, Presence};
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet, MessageOptions,
+    OneofDescriptorProto,
+};
+
+fn field(name: &str, label: Label, ty: Type) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_owned()),
+        label: Some(label as i32),
+        r#type: Some(ty as i32),
+        ..Default::default()
+    }
+}
+
+fn message_field(name: &str, label: Label, type_name: &str) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        type_name: Some(type_name.to_owned()),
+        ..field(name, label, Type::Message)
+    }
+}
+
+fn file(syntax: &str, message: DescriptorProto) -> FileDescriptorSet {
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("shop.proto".to_owned()),
+            package: Some("acme.shop".to_owned()),
+            syntax: Some(syntax.to_owned()),
+            message_type: vec![message],
+            ..Default::default()
+        }],
+    }
+}
+
+fn presences(manifest: &Manifest, rust_path: &str) -> Vec<(String, Presence)> {
+    let message = manifest
+        .messages
+        .iter()
+        .find(|message| message.rust_path == rust_path)
+        .unwrap();
+    message
+        .fields
+        .iter()
+        .map(|field| (field.name.clone(), field.presence))
+        .collect()
+}
+
+fn order() -> DescriptorProto {
+    let mut optional_note = field("note", Label::Optional, Type::String);
+    optional_note.proto3_optional = Some(true);
+    optional_note.oneof_index = Some(1);
+    let mut card = field("card", Label::Optional, Type::String);
+    card.oneof_index = Some(0);
+
+    DescriptorProto {
+        name: Some("Order".to_owned()),
+        field: vec![
+            field("id", Label::Optional, Type::Uint64),
+            field("type", Label::Optional, Type::String),
+            message_field("item", Label::Optional, ".acme.shop.Order.Item"),
+            message_field("items", Label::Repeated, ".acme.shop.Order.Item"),
+            message_field("labels", Label::Repeated, ".acme.shop.Order.LabelsEntry"),
+            card,
+            optional_note,
+        ],
+        nested_type: vec![
+            DescriptorProto {
+                name: Some("Item".to_owned()),
+                field: vec![field("quantity", Label::Required, Type::Uint32)],
+                ..Default::default()
+            },
+            DescriptorProto {
+                name: Some("LabelsEntry".to_owned()),
+                options: Some(MessageOptions {
+                    map_entry: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ],
+        oneof_decl: vec![
+            OneofDescriptorProto {
+                name: Some("payment".to_owned()),
+                ..Default::default()
+            },
+            OneofDescriptorProto {
+                name: Some("_note".to_owned()),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn proto3() {
+    let manifest = Manifest::from_file_descriptor_set(&file("proto3", order()));
+
+    // Map entries don't generate a struct.
+    assert_eq!(manifest.messages.len(), 2);
+    assert_eq!(manifest.messages[0].proto_name, ".acme.shop.Order");
+    assert_eq!(
+        presences(&manifest, "acme::shop::Order"),
+        [
+            ("id".to_owned(), Presence::Plain),
+            ("type".to_owned(), Presence::Plain),
+            ("item".to_owned(), Presence::Optional),
+            ("items".to_owned(), Presence::Repeated),
+            ("labels".to_owned(), Presence::Map),
+            ("note".to_owned(), Presence::Optional),
+            ("payment".to_owned(), Presence::Optional),
+        ]
+    );
+    assert_eq!(
+        presences(&manifest, "acme::shop::order::Item"),
+        [("quantity".to_owned(), Presence::Plain)]
+    );
+}
+
+#[test]
+fn proto2() {
+    let manifest = Manifest::from_file_descriptor_set(&file("proto2", order()));
+
+    // Optional scalars of proto2 are wrapped in an `Option`.
+    assert_eq!(
+        presences(&manifest, "acme::shop::Order")[..2],
+        [
+            ("id".to_owned(), Presence::Optional),
+            ("type".to_owned(), Presence::Optional),
+        ]
+    );
+}
+
+#[test]
+fn display() {
+    let manifest = Manifest::from_file_descriptor_set(&file("proto3", order()));
+
+    let manifest = manifest.to_string();
+    assert!(manifest.contains("proto_path crate::proto\n"));
+    assert!(manifest
+        .contains("message .acme.shop.Order.Item acme::shop::order::Item\nfield quantity plain\n"));
+}
@@ -0,0 +1,141 @@
+use prost_convert_build::NativeConfig;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, FileDescriptorSet, MethodDescriptorProto, ServiceDescriptorProto,
+};
+
+fn field(name: &str, label: Label, ty: Type) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_owned()),
+        label: Some(label as i32),
+        r#type: Some(ty as i32),
+        ..Default::default()
+    }
+}
+
+fn value(name: &str, number: i32) -> EnumValueDescriptorProto {
+    EnumValueDescriptorProto {
+        name: Some(name.to_owned()),
+        number: Some(number),
+        ..Default::default()
+    }
+}
+
+fn fds(syntax: &str) -> FileDescriptorSet {
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("net.proto".to_owned()),
+            package: Some("acme.net".to_owned()),
+            syntax: Some(syntax.to_owned()),
+            message_type: vec![DescriptorProto {
+                name: Some("Host".to_owned()),
+                field: vec![
+                    field("ip", Label::Optional, Type::String),
+                    field("port", Label::Optional, Type::Uint32),
+                    field("aliases", Label::Repeated, Type::String),
+                ],
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Protocol".to_owned()),
+                value: vec![
+                    value("PROTOCOL_UNSPECIFIED", 0),
+                    value("PROTOCOL_TCP", 1),
+                    value("PROTOCOL_UDP", 2),
+                    value("PROTOCOL_DATAGRAM", 2),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+}
+
+#[test]
+fn proto3() {
+    let config = NativeConfig::from_toml(
+        r#"
+        proto_path = "crate::pb"
+
+        [substitutions]
+        ip = "std::net::IpAddr"
+        ".acme.net.Host.aliases" = "url::Host"
+        "#,
+    )
+    .unwrap();
+    let native = config.generate(&fds("proto3")).unwrap();
+
+    assert!(native.contains("#[prost_convert(src = \"crate::pb::acme::net::Host\", canonical)]"));
+    assert!(native.contains("pub ip: std::net::IpAddr,"));
+    assert!(native.contains("pub port: u32,"));
+    assert!(native.contains("pub aliases: Vec<url::Host>,"));
+    // The unspecified variant and the aliases are dropped.
+    assert!(native.contains("allow_unmapped"));
+    assert!(native.contains("pub enum Protocol {\n            Tcp,\n            Udp,\n        }"));
+}
+
+#[test]
+fn proto2() {
+    let native = NativeConfig::default().generate(&fds("proto2")).unwrap();
+
+    assert!(native.contains("#[prost_convert(src = \"crate::proto::acme::net::Host\", canonical)]"));
+    assert!(native.contains("pub port: Option<u32>,"));
+}
+
+#[test]
+fn invalid_substitution() {
+    let config = NativeConfig::from_toml("[substitutions]\nip = \"not a type\"").unwrap();
+
+    assert!(config.generate(&fds("proto3")).is_err());
+}
+
+#[test]
+fn services() {
+    let mut fds = fds("proto3");
+    fds.file[0].service.push(ServiceDescriptorProto {
+        name: Some("HostRegistry".to_owned()),
+        method: vec![
+            MethodDescriptorProto {
+                name: Some("Register".to_owned()),
+                input_type: Some(".acme.net.Host".to_owned()),
+                output_type: Some(".google.protobuf.Empty".to_owned()),
+                client_streaming: Some(true),
+                ..Default::default()
+            },
+            MethodDescriptorProto {
+                name: Some("ListHosts".to_owned()),
+                input_type: Some(".google.protobuf.Empty".to_owned()),
+                output_type: Some(".acme.net.Host".to_owned()),
+                server_streaming: Some(true),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    });
+
+    // Services are only generated on demand.
+    let native = NativeConfig::default().generate(&fds).unwrap();
+    assert!(!native.contains("HostRegistry"));
+
+    let config = NativeConfig::from_toml("services = true").unwrap();
+    // Ignore how the generated code is wrapped.
+    let native = config
+        .generate(&fds)
+        .unwrap()
+        .replace(char::is_whitespace, "");
+
+    assert!(native.contains("pubtraitHostRegistry:Send+Sync+'static{"));
+    assert!(native.contains("pubstructHostRegistryAdapter<T>(pubT);"));
+    assert!(native.contains(
+        "impl<T:HostRegistry>crate::proto::acme::net::host_registry_server::HostRegistry"
+    ));
+    assert!(native.contains(
+        "request:tonic::Request<prost_convert::tonic::BoxStream<crate::native::acme::net::Host>"
+    ));
+    assert!(
+        native.contains("request:tonic::Request<tonic::Streaming<crate::proto::acme::net::Host>>")
+    );
+    assert!(native.contains("typeListHostsStream:prost_convert::tonic::Stream<"));
+    assert!(native.contains("tonic::Response<Self::ListHostsStream>"));
+}
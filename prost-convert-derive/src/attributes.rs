@@ -5,18 +5,69 @@ use syn::NestedMeta::Meta;
 const PROST_CONVERT: &str = "prost_convert";
 
 /// Represents struct or enum attribute information.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Attributes {
     /// The path of the protobuf enum or struct equivalent.
     src: syn::Path,
+    /// Every proto type the native type is converted from, `src` being the first one.
+    versions: Vec<Version>,
     /// The path of the wrapper struct if any.
     wrapper: Option<syn::Path>,
+    /// The field of the wrapper struct holding the proto value, if it is not the snake case name of
+    /// the native type.
+    wrapper_field: Option<syn::Ident>,
+    /// Whether the zero value of a proto field is converted into `None` for every optional native field.
+    empty_as_none: bool,
+    /// The path of the function validating the native value once converted, if any.
+    validate: Option<syn::Path>,
+    /// Whether proto fields or variants missing from the native struct or enum are allowed.
+    allow_unmapped: bool,
+    /// Whether the proto fields missing from the native struct are set to their default value.
+    proto_default: bool,
+    /// Proto fields missing from the native struct and the expression they are set to.
+    set: Vec<(syn::Ident, proc_macro2::TokenStream)>,
+    /// Whether the native type is the one the proto type is converted into by `ProstProto`.
+    canonical: bool,
+    /// Whether to emit a test checking the round trip of random native values.
+    roundtrip_test: bool,
+    /// Whether the proto struct is written by hand, and not in the manifest of
+    /// `prost-convert-build`.
+    skip_manifest: bool,
+    /// The path of the `prost_convert` crate in the generated code.
+    crate_path: syn::Path,
+    /// The foreign type converted by the functions generated for this local mirror of its
+    /// definition, if any.
+    remote: Option<syn::Path>,
+    /// The proto fields of the fields of a tuple struct, by position.
+    fields: Option<Vec<syn::Ident>>,
+}
+
+/// A proto type the native type is converted from, given by `src = "..."` or
+/// `src(v1 = "...", ...)`.
+#[derive(Debug, Clone)]
+pub struct Version {
+    /// The name of the version, used by the field attributes specific to this version.
+    pub name: Option<syn::Ident>,
+    /// The path of the proto type.
+    pub src: syn::Path,
 }
 
 impl Attributes {
     pub fn from_ast(ast: &syn::DeriveInput) -> syn::Result<Self> {
         let mut wrapper = None;
-        let mut src = None;
+        let mut wrapper_field = None;
+        let mut versions: Vec<Version> = Vec::new();
+        let mut empty_as_none = false;
+        let mut validate = None;
+        let mut allow_unmapped = false;
+        let mut proto_default = false;
+        let mut set = Vec::new();
+        let mut canonical = false;
+        let mut roundtrip_test = false;
+        let mut skip_manifest = false;
+        let mut crate_path = None;
+        let mut remote = None;
+        let mut fields = None;
 
         for attr in ast
             .attrs
@@ -32,7 +83,10 @@ impl Attributes {
                     match &m.lit {
                         syn::Lit::Str(attr_value) => {
                             let path = attr_value.parse_with(syn::Path::parse_mod_style)?;
-                            src = Some(path);
+                            versions.push(Version {
+                                name: None,
+                                src: path,
+                            });
                         }
                         other => {
                             return Err(syn::Error::new_spanned(
@@ -42,6 +96,73 @@ impl Attributes {
                         }
                     };
                 }
+                // Parse `#[prost_convert(src(v1 = "foo::v1::Foo", v2 = "foo::v2::Foo"))]`
+                Meta(syn::Meta::List(list)) if list.path == SRC => {
+                    for version in list.nested {
+                        let m = match version {
+                            Meta(NameValue(m)) => m,
+                            other => {
+                                return Err(syn::Error::new_spanned(
+                                    other,
+                                    "expected `prost_convert(src(version = \"...\"))`",
+                                ))
+                            }
+                        };
+                        let (Some(name), syn::Lit::Str(attr_value)) = (m.path.get_ident(), &m.lit)
+                        else {
+                            return Err(syn::Error::new_spanned(
+                                m,
+                                "expected `prost_convert(src(version = \"...\"))`",
+                            ));
+                        };
+                        if versions
+                            .iter()
+                            .any(|version| version.name.as_ref() == Some(name))
+                        {
+                            return Err(syn::Error::new_spanned(
+                                name,
+                                format!("the proto version `{name}` is already defined"),
+                            ));
+                        }
+                        versions.push(Version {
+                            name: Some(name.clone()),
+                            src: attr_value.parse_with(syn::Path::parse_mod_style)?,
+                        });
+                    }
+                }
+                // Parse `#[prost_convert(crate = "foo::prost_convert")]`
+                Meta(NameValue(m)) if m.path == CRATE => {
+                    match &m.lit {
+                        syn::Lit::Str(attr_value) => {
+                            let path = attr_value.parse_with(syn::Path::parse_mod_style)?;
+                            crate_path = Some(path);
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "expected `prost_convert(crate = \"...\")`",
+                            ))
+                        }
+                    };
+                }
+                // Parse `#[prost_convert(fields("x", "y"))]`
+                Meta(syn::Meta::List(list)) if list.path == FIELDS => {
+                    fields = Some(parse_field_names(list)?);
+                }
+                // Parse `#[prost_convert(remote = "std::ops::Range<u32>")]`
+                Meta(NameValue(m)) if m.path == REMOTE => {
+                    match &m.lit {
+                        syn::Lit::Str(attr_value) => {
+                            remote = Some(attr_value.parse::<syn::Path>()?);
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "expected `prost_convert(remote = \"...\")`",
+                            ))
+                        }
+                    };
+                }
                 // Parse `#[prost_convert(wrapper = "foo")]`
                 Meta(NameValue(m)) if m.path == WRAPPER => {
                     match &m.lit {
@@ -52,11 +173,107 @@ impl Attributes {
                         other => {
                             return Err(syn::Error::new_spanned(
                                 other,
-                                "expected `prost_convert(src = \"...\")`",
+                                "expected `prost_convert(wrapper = \"...\")`",
+                            ))
+                        }
+                    };
+                }
+                // Parse `#[prost_convert(roundtrip_test)]`
+                Meta(syn::Meta::Path(word)) if word == ROUNDTRIP_TEST => {
+                    roundtrip_test = true;
+                }
+                // Parse `#[prost_convert(skip_manifest)]`
+                Meta(syn::Meta::Path(word)) if word == SKIP_MANIFEST => {
+                    skip_manifest = true;
+                }
+                // Parse `#[prost_convert(canonical)]`
+                Meta(syn::Meta::Path(word)) if word == CANONICAL => {
+                    canonical = true;
+                }
+                // Parse `#[prost_convert(wrapper_field = "kind")]`
+                Meta(NameValue(m)) if m.path == WRAPPER_FIELD => {
+                    match &m.lit {
+                        syn::Lit::Str(attr_value) => {
+                            wrapper_field = Some(attr_value.parse::<syn::Ident>()?);
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "expected `prost_convert(wrapper_field = \"...\")`",
+                            ))
+                        }
+                    };
+                }
+                // Parse `#[prost_convert(validate = "path::to::fn")]`
+                Meta(NameValue(m)) if m.path == VALIDATE => {
+                    match &m.lit {
+                        syn::Lit::Str(attr_value) => {
+                            let path = attr_value.parse_with(syn::Path::parse_mod_style)?;
+                            validate = Some(path);
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "expected `prost_convert(validate = \"...\")`",
                             ))
                         }
                     };
                 }
+                // Parse `#[prost_convert(empty_as_none)]`
+                Meta(syn::Meta::Path(word)) if word == EMPTY_AS_NONE => {
+                    if !matches!(ast.data, syn::Data::Struct(_)) {
+                        return Err(syn::Error::new_spanned(
+                            word,
+                            "`prost_convert(empty_as_none)` can only be used on structs",
+                        ));
+                    }
+                    empty_as_none = true;
+                }
+                // Parse `#[prost_convert(allow_unmapped)]`
+                Meta(syn::Meta::Path(word)) if word == ALLOW_UNMAPPED => {
+                    allow_unmapped = true;
+                }
+                // Parse `#[prost_convert(proto_default)]`
+                Meta(syn::Meta::Path(word)) if word == PROTO_DEFAULT => {
+                    if !matches!(ast.data, syn::Data::Struct(_)) {
+                        return Err(syn::Error::new_spanned(
+                            word,
+                            "`prost_convert(proto_default)` can only be used on structs",
+                        ));
+                    }
+                    proto_default = true;
+                }
+                // Parse `#[prost_convert(set(field = "expr", ...))]`
+                Meta(syn::Meta::List(list)) if list.path == SET => {
+                    if !matches!(ast.data, syn::Data::Struct(_)) {
+                        return Err(syn::Error::new_spanned(
+                            list.path,
+                            "`prost_convert(set(...))` can only be used on structs",
+                        ));
+                    }
+                    for field in list.nested {
+                        let m = match field {
+                            Meta(NameValue(m)) => m,
+                            other => {
+                                return Err(syn::Error::new_spanned(
+                                    other,
+                                    "expected `prost_convert(set(field = \"expression\"))`",
+                                ))
+                            }
+                        };
+                        match (m.path.get_ident(), &m.lit) {
+                            (Some(name), syn::Lit::Str(expr)) => {
+                                set.push((name.clone(), expr.parse()?));
+                            }
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    m,
+                                    "expected `prost_convert(set(field = \"expression\"))`",
+                                ))
+                            }
+                        }
+                    }
+                }
                 Meta(other) => {
                     return Err(syn::Error::new_spanned(
                         other,
@@ -72,11 +289,60 @@ impl Attributes {
             }
         }
 
+        if let (Some(wrapper_field), None) = (&wrapper_field, &wrapper) {
+            return Err(syn::Error::new_spanned(
+                wrapper_field,
+                "`prost_convert(wrapper_field = \"...\")` requires `prost_convert(wrapper = \"...\")`",
+            ));
+        }
+
+        // The traits can't be implemented for the foreign type, only its conversion functions are
+        // generated.
+        if let Some(remote) = &remote {
+            if versions.len() > 1 {
+                return Err(syn::Error::new_spanned(
+                    remote,
+                    "`prost_convert(remote = \"...\")` can only be converted from one proto type",
+                ));
+            }
+            let unsupported = [
+                (canonical, "canonical"),
+                (roundtrip_test, "roundtrip_test"),
+                (wrapper.is_some(), "wrapper"),
+            ];
+            for (used, name) in unsupported {
+                if used {
+                    return Err(syn::Error::new_spanned(
+                        remote,
+                        format!("`prost_convert({name})` can't be used with `prost_convert(remote = \"...\")`"),
+                    ));
+                }
+            }
+        }
+
+        let Some(first) = versions.first() else {
+            return Err(syn::Error::new(
+                ast.span(),
+                "expected `prost_convert(src = \"...\")`",
+            ));
+        };
+
         Ok(Self {
-            src: src.ok_or_else(|| {
-                syn::Error::new(ast.span(), "expected `prost_convert(src = \"...\")`")
-            })?,
+            src: first.src.clone(),
+            versions,
             wrapper,
+            wrapper_field,
+            empty_as_none,
+            validate,
+            allow_unmapped,
+            proto_default,
+            set,
+            canonical,
+            roundtrip_test,
+            skip_manifest,
+            crate_path: crate_path.unwrap_or_else(|| syn::parse_quote!(prost_convert)),
+            remote,
+            fields,
         })
     }
 
@@ -84,9 +350,498 @@ impl Attributes {
         &self.src
     }
 
+    pub fn versions(&self) -> &[Version] {
+        &self.versions
+    }
+
+    /// Return the attributes of the conversion from the proto type of `version`.
+    pub fn for_version(&self, version: &Version) -> Self {
+        Self {
+            src: version.src.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// The path of the `prost_convert` crate, `prost_convert` unless re-exported by another crate.
+    pub fn crate_path(&self) -> &syn::Path {
+        &self.crate_path
+    }
+
+    pub fn remote(&self) -> Option<&syn::Path> {
+        self.remote.as_ref()
+    }
+
+    pub fn fields(&self) -> Option<&[syn::Ident]> {
+        self.fields.as_deref()
+    }
+
     pub fn wrapper(&self) -> Option<&syn::Path> {
         self.wrapper.as_ref()
     }
+
+    pub fn roundtrip_test(&self) -> bool {
+        self.roundtrip_test
+    }
+
+    pub fn skip_manifest(&self) -> bool {
+        self.skip_manifest
+    }
+
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    pub fn wrapper_field(&self) -> Option<&syn::Ident> {
+        self.wrapper_field.as_ref()
+    }
+
+    pub fn empty_as_none(&self) -> bool {
+        self.empty_as_none
+    }
+
+    pub fn validate(&self) -> Option<&syn::Path> {
+        self.validate.as_ref()
+    }
+
+    /// The proto fields set to their default value are not converted back either.
+    pub fn allow_unmapped(&self) -> bool {
+        self.allow_unmapped || self.proto_default
+    }
+
+    pub fn proto_default(&self) -> bool {
+        self.proto_default
+    }
+
+    pub fn set(&self) -> &[(syn::Ident, proc_macro2::TokenStream)] {
+        &self.set
+    }
+}
+
+/// Represents variant attribute information.
+#[derive(Debug, Default, Clone)]
+pub struct VariantAttributes {
+    /// The path of the proto message held by the variant, when its fields are mapped.
+    src: Option<syn::Path>,
+    /// The fields of the proto message held by the variant, by position of the native fields.
+    fields: Option<Vec<syn::Ident>>,
+    /// Whether proto fields missing from the variant are allowed.
+    allow_unmapped: bool,
+    /// Whether the proto fields missing from the variant are set to their default value.
+    proto_default: bool,
+}
+
+impl VariantAttributes {
+    pub fn from_ast(variant: &syn::Variant) -> syn::Result<Self> {
+        let mut src = None;
+        let mut fields = None;
+        let mut allow_unmapped = false;
+        let mut proto_default = false;
+
+        for attr in variant
+            .attrs
+            .iter()
+            .map(get_prost_convert_meta_item)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+        {
+            match attr {
+                // Parse `#[prost_convert(src = "foo")]`
+                Meta(NameValue(m)) if m.path == SRC => match &m.lit {
+                    syn::Lit::Str(attr_value) => {
+                        src = Some(attr_value.parse_with(syn::Path::parse_mod_style)?);
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `prost_convert(src = \"...\")`",
+                        ))
+                    }
+                },
+                // Parse `#[prost_convert(fields("x", "y"))]`
+                Meta(syn::Meta::List(list)) if list.path == FIELDS => {
+                    fields = Some(parse_field_names(list)?);
+                }
+                // Parse `#[prost_convert(allow_unmapped)]`
+                Meta(syn::Meta::Path(word)) if word == ALLOW_UNMAPPED => {
+                    allow_unmapped = true;
+                }
+                // Parse `#[prost_convert(proto_default)]`
+                Meta(syn::Meta::Path(word)) if word == PROTO_DEFAULT => {
+                    proto_default = true;
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected `prost_convert(src = \"...\")`, `prost_convert(fields(\"...\", ...))`, `prost_convert(allow_unmapped)` or `prost_convert(proto_default)`",
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            src,
+            fields,
+            allow_unmapped,
+            proto_default,
+        })
+    }
+
+    pub fn src(&self) -> Option<&syn::Path> {
+        self.src.as_ref()
+    }
+
+    pub fn fields(&self) -> Option<&[syn::Ident]> {
+        self.fields.as_deref()
+    }
+
+    pub fn allow_unmapped(&self) -> bool {
+        self.allow_unmapped
+    }
+
+    pub fn proto_default(&self) -> bool {
+        self.proto_default
+    }
+}
+
+/// Represents field attribute information.
+#[derive(Debug, Default, Clone)]
+pub struct FieldAttributes {
+    /// What to do with duplicated elements when the field is a set.
+    duplicates: Duplicates,
+    /// Whether the zero value of the proto field is converted into `None`.
+    empty_as_none: bool,
+    /// Validation rules checked on the proto field before converting it.
+    rules: Vec<Rule>,
+    /// The path of the proto field, through nested messages, if it is not the native field name.
+    from: Option<Vec<syn::Ident>>,
+    /// Whether the field is a native struct converted from some fields of the proto struct.
+    flatten: bool,
+    /// Whether the field is missing from the proto struct, and set to its default value.
+    skip: bool,
+    /// The path of the `from_native` and `try_from_proto` functions converting the field, if it
+    /// doesn't implement the conversion traits.
+    with: Option<syn::Path>,
+    /// The attributes specific to a version of the proto struct.
+    versions: Vec<(syn::Ident, FieldVersion)>,
+}
+
+/// The field attributes specific to a proto version, from `#[prost_convert(v2(...))]`.
+#[derive(Debug, Default, Clone)]
+struct FieldVersion {
+    /// Whether the field is missing from this version of the proto struct.
+    skip: bool,
+    /// The path of the proto field in this version, if it is not the one of the other versions.
+    rename: Option<Vec<syn::Ident>>,
+}
+
+/// A validation rule from `#[prost_convert(validate(...))]`.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    MinLen(syn::LitInt),
+    MaxLen(syn::LitInt),
+    Pattern(syn::LitStr),
+    /// The range expression and how it was written.
+    Range(proc_macro2::TokenStream, syn::LitStr),
+    MaxItems(syn::LitInt),
+}
+
+/// Behavior when a `repeated` proto field converted into a set contains duplicated elements.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Silently keep only one of the duplicated elements.
+    #[default]
+    Dedup,
+    /// Fail the conversion.
+    Reject,
+}
+
+impl FieldAttributes {
+    pub fn from_ast(field: &syn::Field) -> syn::Result<Self> {
+        let mut duplicates = Duplicates::default();
+        let mut empty_as_none = false;
+        let mut rules = Vec::new();
+        let mut from = None;
+        let mut flatten = false;
+        let mut with = None;
+        let mut versions: Vec<(syn::Ident, FieldVersion)> = Vec::new();
+
+        for attr in field
+            .attrs
+            .iter()
+            .map(get_prost_convert_meta_item)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+        {
+            match attr {
+                // Parse `#[prost_convert(duplicates = "reject")]`
+                Meta(NameValue(m)) if m.path == DUPLICATES => {
+                    duplicates = match &m.lit {
+                        syn::Lit::Str(attr_value) if attr_value.value() == "dedup" => {
+                            Duplicates::Dedup
+                        }
+                        syn::Lit::Str(attr_value) if attr_value.value() == "reject" => {
+                            Duplicates::Reject
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "expected `prost_convert(duplicates = \"dedup\")` or `prost_convert(duplicates = \"reject\")`",
+                            ))
+                        }
+                    };
+                }
+                // Parse `#[prost_convert(empty_as_none)]`
+                Meta(syn::Meta::Path(word)) if word == EMPTY_AS_NONE => {
+                    empty_as_none = true;
+                }
+                // Parse `#[prost_convert(validate(min_len = 1, ...))]`
+                Meta(syn::Meta::List(list)) if list.path == VALIDATE => {
+                    for rule in list.nested {
+                        rules.push(Rule::from_meta(rule)?);
+                    }
+                }
+                // Parse `#[prost_convert(from = "message.field")]`
+                Meta(NameValue(m)) if m.path == FROM => match &m.lit {
+                    syn::Lit::Str(path) => {
+                        from = Some(parse_proto_path(path)?);
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `prost_convert(from = \"...\")`",
+                        ))
+                    }
+                },
+                // Parse `#[prost_convert(flatten)]`
+                Meta(syn::Meta::Path(word)) if word == FLATTEN => {
+                    flatten = true;
+                }
+                // Parse `#[prost_convert(with = "RangeDef")]`
+                Meta(NameValue(m)) if m.path == WITH => match &m.lit {
+                    syn::Lit::Str(path) => {
+                        with = Some(path.parse_with(syn::Path::parse_mod_style)?);
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `prost_convert(with = \"...\")`",
+                        ))
+                    }
+                },
+                // Parse `#[prost_convert(v2(skip))]` or `#[prost_convert(v2(rename = "field"))]`
+                Meta(syn::Meta::List(list)) if list.path.get_ident().is_some() => {
+                    // A path which is an identifier has a single segment.
+                    let name = list.path.segments[0].ident.clone();
+                    if versions.iter().any(|(other, _)| *other == name) {
+                        return Err(syn::Error::new_spanned(
+                            name,
+                            "the attributes of a proto version must be given at once",
+                        ));
+                    }
+                    versions.push((name, FieldVersion::from_meta(list.nested)?));
+                }
+                Meta(other) => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "unknown prost_convert field attribute",
+                    ));
+                }
+                syn::NestedMeta::Lit(lit) => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "unexpected literal in prost_derive field attribute",
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            duplicates,
+            empty_as_none,
+            rules,
+            from,
+            flatten,
+            skip: false,
+            with,
+            versions,
+        })
+    }
+
+    /// Return the attributes of the field for the proto type of `version`.
+    pub fn for_version(&self, version: Option<&syn::Ident>) -> Self {
+        let mut attrs = Self {
+            versions: Vec::new(),
+            ..self.clone()
+        };
+        let field_version = self
+            .versions
+            .iter()
+            .find(|(name, _)| Some(name) == version)
+            .map(|(_, field_version)| field_version);
+        if let Some(field_version) = field_version {
+            if field_version.skip {
+                attrs.skip = true;
+                attrs.flatten = false;
+            }
+            if let Some(rename) = &field_version.rename {
+                attrs.from = Some(rename.clone());
+            }
+        }
+        attrs
+    }
+
+    /// The names of the proto versions the field has specific attributes for.
+    pub fn version_names(&self) -> impl Iterator<Item = &syn::Ident> {
+        self.versions.iter().map(|(name, _)| name)
+    }
+
+    pub fn duplicates(&self) -> Duplicates {
+        self.duplicates
+    }
+
+    pub fn empty_as_none(&self) -> bool {
+        self.empty_as_none
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn from(&self) -> Option<&[syn::Ident]> {
+        self.from.as_deref()
+    }
+
+    pub fn flatten(&self) -> bool {
+        self.flatten
+    }
+
+    pub fn skip(&self) -> bool {
+        self.skip
+    }
+
+    pub fn with(&self) -> Option<&syn::Path> {
+        self.with.as_ref()
+    }
+}
+
+impl FieldVersion {
+    fn from_meta(nested: impl IntoIterator<Item = syn::NestedMeta>) -> syn::Result<Self> {
+        const EXPECTED: &str = "expected `skip` or `rename = \"...\"`";
+        let mut version = Self::default();
+        for meta in nested {
+            match meta {
+                Meta(syn::Meta::Path(word)) if word == SKIP => {
+                    version.skip = true;
+                }
+                Meta(NameValue(m)) if m.path == RENAME => match &m.lit {
+                    syn::Lit::Str(path) => {
+                        version.rename = Some(parse_proto_path(path)?);
+                    }
+                    other => return Err(syn::Error::new_spanned(other, EXPECTED)),
+                },
+                other => return Err(syn::Error::new_spanned(other, EXPECTED)),
+            }
+        }
+        if let (true, Some(rename)) = (version.skip, &version.rename) {
+            return Err(syn::Error::new_spanned(
+                &rename[0],
+                "a skipped field can't be renamed",
+            ));
+        }
+        Ok(version)
+    }
+}
+
+/// Parse the path of a proto field through nested messages, such as `network.host`.
+fn parse_proto_path(path: &syn::LitStr) -> syn::Result<Vec<syn::Ident>> {
+    let segments = path
+        .value()
+        .split('.')
+        .map(syn::parse_str::<syn::Ident>)
+        .collect::<syn::Result<Vec<_>>>()
+        .map_err(|_| {
+            syn::Error::new_spanned(
+                path,
+                "expected a proto field or a dotted path such as `network.host`",
+            )
+        })?;
+    Ok(segments
+        .into_iter()
+        .map(|mut segment| {
+            segment.set_span(path.span());
+            segment
+        })
+        .collect())
+}
+
+/// Parse the proto fields of `fields("x", "y")`.
+fn parse_field_names(list: syn::MetaList) -> syn::Result<Vec<syn::Ident>> {
+    list.nested
+        .iter()
+        .map(|name| match name {
+            syn::NestedMeta::Lit(syn::Lit::Str(name)) => {
+                let mut ident = name.parse::<syn::Ident>().map_err(|_| {
+                    syn::Error::new_spanned(name, "expected the name of a proto field")
+                })?;
+                ident.set_span(name.span());
+                Ok(ident)
+            }
+            other => Err(syn::Error::new_spanned(
+                other,
+                "expected `prost_convert(fields(\"...\", ...))`",
+            )),
+        })
+        .collect()
+}
+
+impl Rule {
+    fn from_meta(meta: syn::NestedMeta) -> syn::Result<Self> {
+        const EXPECTED: &str =
+            "expected one of `min_len`, `max_len`, `pattern`, `range` or `max_items` validation rules";
+        let m = match meta {
+            Meta(NameValue(m)) => m,
+            other => return Err(syn::Error::new_spanned(other, EXPECTED)),
+        };
+        let int = |lit: &syn::Lit| match lit {
+            syn::Lit::Int(int) => {
+                int.base10_parse::<usize>()?;
+                Ok(int.clone())
+            }
+            other => Err(syn::Error::new_spanned(other, "expected an integer")),
+        };
+        let string = |lit: &syn::Lit| match lit {
+            syn::Lit::Str(string) => Ok(string.clone()),
+            other => Err(syn::Error::new_spanned(other, "expected a string")),
+        };
+
+        if m.path == MIN_LEN {
+            Ok(Rule::MinLen(int(&m.lit)?))
+        } else if m.path == MAX_LEN {
+            Ok(Rule::MaxLen(int(&m.lit)?))
+        } else if m.path == MAX_ITEMS {
+            Ok(Rule::MaxItems(int(&m.lit)?))
+        } else if m.path == PATTERN {
+            let pattern = string(&m.lit)?;
+            // Reject an invalid expression now rather than when the first message is received.
+            regex_syntax::Parser::new()
+                .parse(&pattern.value())
+                .map_err(|err| syn::Error::new_spanned(&pattern, err))?;
+            Ok(Rule::Pattern(pattern))
+        } else if m.path == RANGE {
+            let repr = string(&m.lit)?;
+            if !repr.value().contains("..") {
+                return Err(syn::Error::new_spanned(
+                    repr,
+                    "expected a range such as `1..=100`",
+                ));
+            }
+            Ok(Rule::Range(repr.parse()?, repr))
+        } else {
+            Err(syn::Error::new_spanned(m.path, EXPECTED))
+        }
+    }
 }
 
 /// Extract all attributes that are inside a `#[prost_convert(...)]` if the attribute is
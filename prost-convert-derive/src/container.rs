@@ -1,12 +1,14 @@
 use syn::{punctuated::Punctuated, spanned::Spanned, Token};
 
-use crate::attributes::Attributes;
+use crate::attributes::{Attributes, FieldAttributes, VariantAttributes, Version};
 
 /// A source data structure annotated with `#[derive(ProstConvert)]`,
 /// parsed into an internal representation.
 pub struct Container<'a> {
     /// The struct or enum name (without generics).
     pub ident: syn::Ident,
+    /// The visibility of the struct or enum.
+    pub vis: syn::Visibility,
     /// Attributes on the structure, parsed for `prost_derive`.
     pub attrs: Attributes,
     /// The contents of the struct or enum.
@@ -16,25 +18,33 @@ pub struct Container<'a> {
 /// The fields of a struct or enum.
 ///
 /// Analogous to `syn::Data`.
+#[derive(Clone)]
 pub enum Data<'a> {
     Enum(Vec<Variant<'a>>),
     Struct(Vec<Field<'a>>),
 }
 
 /// A variant of an enum.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Variant<'a> {
     pub ident: syn::Ident,
     pub style: Style,
     pub fields: Vec<Field<'a>>,
+    /// Attributes on the variant, parsed for `prost_derive`.
+    pub attrs: VariantAttributes,
 }
 
 /// A field of a struct.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Field<'a> {
-    // TODO: check if unammed fields does't break the macro.
+    /// The name of the proto field, which is the native one unless the field is unnamed and
+    /// given by `prost_convert(fields(...))`.
     pub name: Option<syn::Ident>,
+    /// The native field, by name or by position.
+    pub member: syn::Member,
     pub ty: &'a syn::Type,
+    /// Attributes on the field, parsed for `prost_derive`.
+    pub attrs: FieldAttributes,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -51,9 +61,22 @@ pub enum Style {
 
 impl<'a> Container<'a> {
     pub fn from_ast(ast: &'a syn::DeriveInput) -> syn::Result<Self> {
+        let attrs = Attributes::from_ast(ast)?;
         let data = match &ast.data {
-            syn::Data::Struct(data) => Data::Struct(struct_from_ast(&data.fields).1),
-            syn::Data::Enum(data) => Data::Enum(enum_from_ast(&data.variants)),
+            syn::Data::Struct(data) => {
+                let mut fields = struct_from_ast(&data.fields)?.1;
+                if let Some(names) = attrs.fields() {
+                    name_fields(&mut fields, names, &ast.ident)?;
+                }
+                Data::Struct(fields)
+            }
+            syn::Data::Enum(_) if attrs.fields().is_some() => {
+                return Err(syn::Error::new_spanned(
+                    &ast.ident,
+                    "`prost_convert(fields(...))` can only be used on tuple structs and tuple variants",
+                ));
+            }
+            syn::Data::Enum(data) => Data::Enum(enum_from_ast(&data.variants)?),
             syn::Data::Union(_) => {
                 return Err(syn::Error::new(
                     ast.span(),
@@ -64,12 +87,46 @@ impl<'a> Container<'a> {
 
         Ok(Container {
             ident: ast.ident.clone(),
-            attrs: Attributes::from_ast(ast)?,
+            vis: ast.vis.clone(),
+            attrs,
             data,
         })
     }
 
-    /// Return a boolean indicating if the container is an enum and its fields are [`Unit`].
+    /// Return the container converted from the proto type of `version`, with the attributes of
+    /// its fields specific to this version.
+    pub fn for_version(&self, version: &Version) -> Self {
+        let name = version.name.as_ref();
+        let fields = |fields: &[Field<'a>]| {
+            fields
+                .iter()
+                .map(|field| Field {
+                    attrs: field.attrs.for_version(name),
+                    ..field.clone()
+                })
+                .collect()
+        };
+        let data = match &self.data {
+            Data::Struct(data) => Data::Struct(fields(data)),
+            Data::Enum(variants) => Data::Enum(
+                variants
+                    .iter()
+                    .map(|variant| Variant {
+                        fields: fields(&variant.fields),
+                        ..variant.clone()
+                    })
+                    .collect(),
+            ),
+        };
+        Container {
+            ident: self.ident.clone(),
+            vis: self.vis.clone(),
+            attrs: self.attrs.for_version(version),
+            data,
+        }
+    }
+
+    /// Return the variants of the container if it is an enum whose variants are all [`Unit`].
     /// For intance
     /// ```rust
     /// enum Os {
@@ -77,52 +134,96 @@ impl<'a> Container<'a> {
     ///     Windows,
     /// }
     /// ```
-    /// return true.
+    /// return its variants.
     ///
     /// [`Unit`]: https://docs.rs/syn/latest/syn/enum.Fields.html
-    pub fn unit_variants(&self) -> Option<&[Variant]> {
-        if let Data::Enum(ref variants) = self.data {
-            if let Some(variant) = variants.first() {
-                if matches!(variant.style, Style::Unit) {
-                    return Some(variants);
-                }
+    pub fn unit_variants(&self) -> Option<&[Variant<'_>]> {
+        match &self.data {
+            Data::Enum(variants)
+                if !variants.is_empty()
+                    && variants
+                        .iter()
+                        .all(|variant| matches!(variant.style, Style::Unit)) =>
+            {
+                Some(variants)
             }
+            _ => None,
         }
-        None
     }
 }
 
-fn struct_from_ast(fields: &syn::Fields) -> (Style, Vec<Field<'_>>) {
-    match fields {
-        syn::Fields::Named(fields) => (Style::Struct, fields_from_ast(&fields.named)),
+fn struct_from_ast(fields: &syn::Fields) -> syn::Result<(Style, Vec<Field<'_>>)> {
+    Ok(match fields {
+        syn::Fields::Named(fields) => (Style::Struct, fields_from_ast(&fields.named)?),
         syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
-            (Style::Newtype, fields_from_ast(&fields.unnamed))
+            (Style::Newtype, fields_from_ast(&fields.unnamed)?)
         }
-        syn::Fields::Unnamed(fields) => (Style::Tuple, fields_from_ast(&fields.unnamed)),
+        syn::Fields::Unnamed(fields) => (Style::Tuple, fields_from_ast(&fields.unnamed)?),
         syn::Fields::Unit => (Style::Unit, Vec::new()),
-    }
+    })
 }
 
-fn enum_from_ast(variants: &Punctuated<syn::Variant, Token![,]>) -> Vec<Variant<'_>> {
+fn enum_from_ast(variants: &Punctuated<syn::Variant, Token![,]>) -> syn::Result<Vec<Variant<'_>>> {
     variants
         .iter()
         .map(|variant| {
-            let (style, fields) = struct_from_ast(&variant.fields);
-            Variant {
+            let (style, mut fields) = struct_from_ast(&variant.fields)?;
+            let attrs = VariantAttributes::from_ast(variant)?;
+            if let Some(names) = attrs.fields() {
+                name_fields(&mut fields, names, &variant.ident)?;
+            }
+            Ok(Variant {
                 ident: variant.ident.clone(),
                 fields,
                 style,
-            }
+                attrs,
+            })
         })
         .collect()
 }
 
-fn fields_from_ast(fields: &Punctuated<syn::Field, Token![,]>) -> Vec<Field<'_>> {
+/// Give the unnamed fields of a tuple struct or variant the names of their proto fields.
+fn name_fields(fields: &mut [Field], names: &[syn::Ident], ident: &syn::Ident) -> syn::Result<()> {
+    if fields.iter().any(|field| field.name.is_some()) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "`prost_convert(fields(...))` can only be used on tuple structs and tuple variants",
+        ));
+    }
+    if fields.len() != names.len() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!(
+                "`prost_convert(fields(...))` names {} proto fields for {} fields",
+                names.len(),
+                fields.len()
+            ),
+        ));
+    }
+    for (field, name) in fields.iter_mut().zip(names) {
+        field.name = Some(name.clone());
+    }
+    Ok(())
+}
+
+fn fields_from_ast(fields: &Punctuated<syn::Field, Token![,]>) -> syn::Result<Vec<Field<'_>>> {
     fields
         .iter()
-        .map(|field| Field {
-            name: field.ident.clone(),
-            ty: &field.ty,
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index {
+                    index: i as u32,
+                    span: field.ty.span(),
+                }),
+            };
+            Ok(Field {
+                name: field.ident.clone(),
+                member,
+                ty: &field.ty,
+                attrs: FieldAttributes::from_ast(field)?,
+            })
         })
         .collect()
 }
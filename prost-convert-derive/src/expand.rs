@@ -1,45 +1,358 @@
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 
 use crate::{
-    attributes::Attributes,
+    attributes::{Attributes, Duplicates, Rule},
     container::{Container, Data, Field, Style, Variant},
+    manifest::{self, Message, Presence},
 };
 
 pub fn expand_derive_prost_convert(ast: syn::DeriveInput) -> syn::Result<TokenStream> {
     let container = Container::from_ast(&ast)?;
+    check_versions(&container)?;
+
+    // A mirror of a foreign type only has the functions converting the foreign type.
+    if let Some(remote) = container.attrs.remote() {
+        return expand_remote(&container, remote);
+    }
+
+    // The conversions are implemented for every proto type, with the field attributes specific to
+    // it. The other impls only use the first one.
+    let versions = container
+        .attrs
+        .versions()
+        .iter()
+        .map(|version| container.for_version(version))
+        .collect::<Vec<_>>();
+    let mut expanded = TokenStream::new();
+    for (n, version) in versions.iter().enumerate() {
+        let test_suffix = match &container.attrs.versions()[n].name {
+            Some(name) => format!("_{name}"),
+            None if n == 0 => String::new(),
+            None => format!("_{n}"),
+        };
+        expanded.extend(expand_conversions(version, &test_suffix)?);
+    }
+    let container = &versions[0];
 
     let native = &container.ident;
     let proto = &container.attrs.src();
-    let from_native_body = from_native_body(&container);
-    let try_from_proto_body = try_from_proto_body(&container);
+    let krate = container.attrs.crate_path();
+
+    expanded.extend(quote!(
+        impl #krate::ProstNative for #native {
+            type Proto = #proto;
+        }
+    ));
+
+    if container.attrs.canonical() {
+        expanded.extend(quote!(
+            impl #krate::ProstProto for #proto {
+                type Native = #native;
+            }
+        ));
+    }
+
+    if let Some(wrapper) = container.attrs.wrapper() {
+        let wrapper_field = container
+            .attrs
+            .wrapper_field()
+            .cloned()
+            .unwrap_or_else(|| ident_to_snake_case(native));
+        expanded.extend(wrapper_struct_impl(wrapper, &wrapper_field, native, krate));
+    }
+
+    // Prost enums are stored as `i32` in every version, only one conversion can be implemented.
+    if let Some(variants) = container.unit_variants() {
+        expanded.extend(enum_i32_convertion(variants, container));
+    }
+
+    Ok(expanded)
+}
+
+/// Return the conversions between the native type and the proto type of `container`.
+fn expand_conversions(container: &Container, test_suffix: &str) -> syn::Result<TokenStream> {
+    check_attributes(container)?;
+    let message = proto_message(container)?;
+
+    let native = &container.ident;
+    let proto = &container.attrs.src();
+    let krate = container.attrs.crate_path();
+    let from_native_body = from_native_body(container, message.as_ref());
+    let try_from_proto_body = try_from_proto_body(container, message.as_ref());
 
     let mut expanded = quote!(
 
-        impl prost_convert::FromNative<#native> for #proto {
+        impl #krate::FromNative<#native> for #proto {
             fn from_native(value: #native) -> Self {
                 #from_native_body
             }
         }
 
-        impl prost_convert::TryFromProto<#proto> for #native {
-            fn try_from_proto(value: #proto) -> std::result::Result<Self, prost_convert::ProstConvertError> {
+        impl #krate::TryFromProto<#proto> for #native {
+            fn try_from_proto(value: #proto) -> std::result::Result<Self, #krate::ProstConvertError> {
                 #try_from_proto_body
             }
         }
     );
 
-    if let Some(wrapper) = container.attrs.wrapper() {
-        expanded.extend(wrapper_struct_impl(wrapper, &container.ident));
+    if let Data::Struct(data) = &container.data {
+        // A struct mapping only some proto fields can be flattened into another native struct.
+        if container.attrs.proto_default() {
+            expanded.extend(proto_fields_impl(data, container, message.as_ref()));
+        }
+        expanded.extend(flattened_fields_check(
+            data,
+            &container.attrs,
+            message.as_ref(),
+        ));
     }
 
-    if let Some(variants) = container.unit_variants() {
-        expanded.extend(enum_i32_convertion(variants, &container));
+    if container.attrs.roundtrip_test() {
+        let test = syn::Ident::new(
+            &format!(
+                "prost_convert_roundtrip_{}{test_suffix}",
+                ident_to_snake_case(native)
+            ),
+            Span::call_site(),
+        );
+        expanded.extend(quote!(
+            #[cfg(test)]
+            #[test]
+            fn #test() {
+                #krate::testing::check_roundtrip::<#proto, #native>();
+            }
+        ));
     }
 
     Ok(expanded)
 }
 
+/// Check that the field attributes specific to a proto version refer to a named version.
+fn check_versions(container: &Container) -> syn::Result<()> {
+    let fields: Vec<&Field> = match &container.data {
+        Data::Struct(fields) => fields.iter().collect(),
+        Data::Enum(variants) => variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .collect(),
+    };
+    let versions = container.attrs.versions();
+    for name in fields.iter().flat_map(|field| field.attrs.version_names()) {
+        if matches!(container.data, Data::Enum(_)) {
+            return Err(syn::Error::new_spanned(
+                name,
+                "the attributes of a proto version can only be used on struct fields",
+            ));
+        }
+        if !versions
+            .iter()
+            .any(|version| version.name.as_ref() == Some(name))
+        {
+            let names = versions
+                .iter()
+                .filter_map(|version| version.name.as_ref())
+                .map(|name| format!("`{name}`"))
+                .collect::<Vec<_>>();
+            let message = if names.is_empty() {
+                format!(
+                    "unknown proto version `{name}`, versions are named with `prost_convert(src({name} = \"...\"))`"
+                )
+            } else {
+                format!(
+                    "unknown proto version `{name}`, expected one of {}",
+                    names.join(", ")
+                )
+            };
+            return Err(syn::Error::new_spanned(name, message));
+        }
+    }
+    Ok(())
+}
+
+/// Check the attributes which depend on the type they are applied to.
+fn check_attributes(container: &Container) -> syn::Result<()> {
+    let fields = match &container.data {
+        Data::Enum(variants) => {
+            if container.attrs.allow_unmapped() && container.unit_variants().is_none() {
+                return Err(syn::Error::new_spanned(
+                    &container.ident,
+                    "`prost_convert(allow_unmapped)` can only be used on enums with unit variants",
+                ));
+            }
+            for variant in variants {
+                if matches!(variant.style, Style::Tuple) && variant.attrs.fields().is_none() {
+                    return Err(syn::Error::new_spanned(
+                        &variant.ident,
+                        "the fields of a tuple variant are mapped to the fields of a proto message with `prost_convert(fields(\"...\", ...))`",
+                    ));
+                }
+                let attrs = &variant.attrs;
+                if attrs.fields().is_some() && attrs.src().is_none() {
+                    return Err(syn::Error::new_spanned(
+                        &variant.ident,
+                        "the proto message held by a tuple variant is named with `prost_convert(src = \"...\")`",
+                    ));
+                }
+                if attrs.fields().is_none()
+                    && (attrs.src().is_some() || attrs.allow_unmapped() || attrs.proto_default())
+                {
+                    return Err(syn::Error::new_spanned(
+                        &variant.ident,
+                        "`prost_convert(src = \"...\")`, `prost_convert(allow_unmapped)` and `prost_convert(proto_default)` can only be used on variants with `prost_convert(fields(\"...\", ...))`",
+                    ));
+                }
+                // The rules are checked on the fields of the message held by the variant.
+                if attrs.fields().is_none() {
+                    if let Some(field) = variant
+                        .fields
+                        .iter()
+                        .find(|field| !field.attrs.rules().is_empty())
+                    {
+                        return Err(syn::Error::new_spanned(
+                            field.ty,
+                            "`prost_convert(validate(...))` can only be used on struct fields and on the fields of variants with `prost_convert(fields(\"...\", ...))`",
+                        ));
+                    }
+                }
+            }
+            for field in variants.iter().flat_map(|variant| &variant.fields) {
+                if field.attrs.empty_as_none() {
+                    return Err(syn::Error::new_spanned(
+                        field.ty,
+                        "`prost_convert(empty_as_none)` can only be used on struct fields",
+                    ));
+                }
+                if field.attrs.from().is_some() || field.attrs.flatten() {
+                    return Err(syn::Error::new_spanned(
+                        field.ty,
+                        "`prost_convert(from = \"...\")` and `prost_convert(flatten)` can only be used on struct fields",
+                    ));
+                }
+                if let Some(with) = field.attrs.with() {
+                    return Err(syn::Error::new_spanned(
+                        with,
+                        "`prost_convert(with = \"...\")` can only be used on struct fields",
+                    ));
+                }
+            }
+            return Ok(());
+        }
+        Data::Struct(fields) => fields,
+    };
+    if let Some(field) = fields.iter().find(|field| field.name.is_none()) {
+        return Err(syn::Error::new_spanned(
+            field.ty,
+            "the fields of a tuple struct are mapped to proto fields with `prost_convert(fields(\"...\", ...))`",
+        ));
+    }
+    let paths = fields.iter().map(proto_path).collect::<Vec<_>>();
+    for (n, (field, path)) in fields.iter().zip(&paths).enumerate() {
+        if field.attrs.flatten()
+            && (field.attrs.from().is_some()
+                || field.attrs.empty_as_none()
+                || !field.attrs.rules().is_empty()
+                || field.attrs.with().is_some()
+                || field.attrs.duplicates() != Duplicates::Dedup)
+        {
+            return Err(syn::Error::new_spanned(
+                field.ty,
+                "`prost_convert(flatten)` can't be combined with other field attributes",
+            ));
+        }
+        // A proto field can't be read by two native fields, nor a message and one of its fields.
+        let Some(path) = path else { continue };
+        let overlapping = paths[..n].iter().flatten().find(|other| {
+            let len = other.len().min(path.len());
+            other[..len] == path[..len]
+        });
+        if let Some(other) = overlapping {
+            let other = other
+                .iter()
+                .map(|segment| segment.to_string())
+                .collect::<Vec<_>>();
+            return Err(syn::Error::new_spanned(
+                field.name.as_ref(),
+                format!("the proto field `{}` is already mapped", other.join(".")),
+            ));
+        }
+    }
+    for (name, _) in container.attrs.set() {
+        if paths.iter().flatten().any(|path| &path[0] == name) {
+            return Err(syn::Error::new_spanned(
+                name,
+                "a field can't be both in the native struct and in `prost_convert(set(...))`",
+            ));
+        }
+    }
+    for field in fields {
+        if let (Some(with), Duplicates::Reject) = (field.attrs.with(), field.attrs.duplicates()) {
+            return Err(syn::Error::new_spanned(
+                with,
+                "`prost_convert(with = \"...\")` can't be combined with `prost_convert(duplicates = \"reject\")`",
+            ));
+        }
+        if field.attrs.empty_as_none() && inner_ty("Option", field.ty).is_none() {
+            return Err(syn::Error::new_spanned(
+                field.ty,
+                "`prost_convert(empty_as_none)` can only be used on `Option` fields",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Return the proto message of a struct, if the crate has a manifest generated by
+/// `prost-convert-build`, and check that it has the fields of the native struct.
+fn proto_message(container: &Container) -> syn::Result<Option<Message>> {
+    let Data::Struct(fields) = &container.data else {
+        return Ok(None);
+    };
+    if container.attrs.skip_manifest() {
+        return Ok(None);
+    }
+    let Some(message) = manifest::lookup(container.attrs.src())? else {
+        return Ok(None);
+    };
+    let roots = fields
+        .iter()
+        .filter_map(proto_path)
+        .map(|path| path[0].clone())
+        .collect::<Vec<_>>();
+    let set = container.attrs.set().iter().map(|(name, _)| name);
+    for name in roots.iter().chain(set) {
+        if message.presence(&name.to_string()).is_none() {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!(
+                    "no field `{}` in proto message `{}`",
+                    name.to_string().trim_start_matches("r#"),
+                    message.proto_name
+                ),
+            ));
+        }
+    }
+    Ok(Some(message))
+}
+
+/// Return `true` if the native field is an `Option` while the proto field is never unset.
+/// This can only be known from the manifest.
+fn always_set(field: &Field, message: Option<&Message>) -> bool {
+    // The manifest only knows the fields of the message itself, not of the nested ones.
+    let presence = match (message, proto_path(field).as_deref()) {
+        (Some(message), Some([name])) => message.presence(&name.to_string()),
+        _ => None,
+    };
+    inner_ty("Option", field.ty).is_some()
+        && presence.is_some_and(|presence| presence != Presence::Optional)
+}
+
+/// Return `true` if the zero value of the proto field must be converted into `None`.
+fn empty_as_none(field: &Field, attrs: &Attributes) -> bool {
+    inner_ty("Option", field.ty).is_some() && (field.attrs.empty_as_none() || attrs.empty_as_none())
+}
+
 /// If the type wrap another type (i.e. `Option<T>` or `Vec<T>`) return the inner type.
 fn inner_ty<'a>(wrapper: &str, field: &'a syn::Type) -> Option<&'a syn::Type> {
     // Faudrait créer tout les cas, genre std::option::Option<T>, option::Option<T>, Option<T>
@@ -64,60 +377,245 @@ fn inner_ty<'a>(wrapper: &str, field: &'a syn::Type) -> Option<&'a syn::Type> {
 }
 
 /// return the body of the `from_native` function.
-fn from_native_body(cont: &Container) -> TokenStream {
+fn from_native_body(cont: &Container, message: Option<&Message>) -> TokenStream {
     match &cont.data {
-        Data::Enum(data) => from_native_enum(data, &cont.ident),
-        Data::Struct(data) => from_native_struct(data),
+        Data::Enum(data) => from_native_enum(data, cont),
+        Data::Struct(data) => from_native_struct(data, &cont.attrs, message),
     }
 }
 
-fn from_native_struct(data: &[Field]) -> TokenStream {
-    let fields = data.iter().map(|field| {
-        let name = &field.name;
-        // If the native field is an option we don't to flat the proto one.
-        if inner_ty("Option", field.ty).is_some() {
-            quote!(#name: value.#name.map(|field| field.into_proto()))
-        } else {
-            quote!(#name: value.#name.into_proto())
+fn from_native_struct(
+    data: &[Field],
+    attrs: &Attributes,
+    message: Option<&Message>,
+) -> TokenStream {
+    let krate = attrs.crate_path();
+    let mut fields = Vec::new();
+    let mut nested = Vec::new();
+    let mut roots: Vec<&syn::Ident> = Vec::new();
+    let mut flattened = Vec::new();
+    let paths = data.iter().map(proto_path).collect::<Vec<_>>();
+    // A skipped field is missing from the proto struct.
+    for (field, path) in data
+        .iter()
+        .zip(&paths)
+        .filter(|(field, _)| !field.attrs.skip())
+    {
+        let member = &field.member;
+        match path.as_deref() {
+            None => flattened.push(quote!(
+                #krate::IntoProtoFields::into_proto_fields(value.#member, &mut proto);
+            )),
+            Some(path @ [proto_name]) => {
+                let convert = from_native_field(field, path, attrs, message);
+                fields.push(quote!(#proto_name: #convert));
+            }
+            Some(path) => {
+                if !roots.contains(&&path[0]) {
+                    roots.push(&path[0]);
+                }
+                let place = nested_place(quote!(proto), path, krate);
+                let convert = from_native_field(field, path, attrs, message);
+                nested.push(quote!(#place = #convert;));
+            }
         }
-    });
+    }
 
-    quote!(
-        use prost_convert::IntoProto;
-        Self {
-            #(#fields),*
+    let set = attrs.set().iter().map(|(name, expr)| quote!(#name: #expr));
+    let mut unmapped = Vec::new();
+    let rest = if attrs.proto_default() {
+        Some(quote!(..std::default::Default::default()))
+    } else if flattened.is_empty() {
+        None
+    } else if let Some(names) = flattened_proto_idents(data, attrs, message) {
+        // The fields set by the flattened structs are listed, so that the proto struct is still
+        // built exhaustively.
+        unmapped = names;
+        None
+    } else {
+        Some(quote!(..std::default::Default::default()))
+    };
+    let proto_ty = attrs.src();
+    let proto = quote!(
+        #proto_ty {
+            #(#fields,)*
+            #(#roots: std::default::Default::default(),)*
+            #(#set,)*
+            #(#unmapped: std::default::Default::default(),)*
+            #rest
         }
+    );
+
+    if nested.is_empty() && flattened.is_empty() {
+        return proto;
+    }
+    quote!(
+        let mut proto = #proto;
+        #(#nested)*
+        #(#flattened)*
+        proto
     )
 }
 
-fn from_native_enum(data: &[Variant], native: &syn::Ident) -> TokenStream {
+/// Return the expression converting the native field `value.#name` into the proto field at
+/// `path`.
+fn from_native_field(
+    field: &Field,
+    path: &[syn::Ident],
+    attrs: &Attributes,
+    message: Option<&Message>,
+) -> TokenStream {
+    let krate = attrs.crate_path();
+    let member = &field.member;
+    let (convert_fn, call) = field_fn(path, field.ty);
+    let convert = match field.attrs.with() {
+        // The function given by `with` returns the proto type, which prost may wrap in an
+        // `Option`.
+        Some(_) => quote!(
+            fn #convert_fn<P, F: #krate::WithField<P>>(value: P) -> F {
+                F::from_proto(value)
+            }
+        ),
+        None => quote!(
+            fn #convert_fn<N, P: #krate::FromNative<N>>(value: N) -> P {
+                P::from_native(value)
+            }
+        ),
+    };
+    let call = |arg: TokenStream| match field.attrs.with() {
+        Some(with) => quote_spanned!(with.span()=> #call(#with::from_native(#arg))),
+        None => quote_spanned!(call.span()=> #call(#arg)),
+    };
+    if empty_as_none(field, attrs) || always_set(field, message) {
+        // `None` becomes the zero value of the proto field.
+        let call = call(quote!(field));
+        quote!({ #convert value.#member.map(|field| #call).unwrap_or_default() })
+    } else if inner_ty("Option", field.ty).is_some() {
+        // If the native field is an option we don't to flat the proto one.
+        let call = call(quote!(field));
+        quote!({ #convert value.#member.map(|field| #call) })
+    } else {
+        let call = call(quote!(value.#member));
+        quote!({ #convert #call })
+    }
+}
+
+/// Return the function converting the value of a newtype variant, named after the proto field of
+/// the oneof, and the identifier calling it. See [`field_fn`].
+fn variant_fn(variant: &Variant) -> (syn::Ident, syn::Ident) {
+    let path = [ident_to_snake_case(&variant.ident)];
+    field_fn(&path, variant.fields[0].ty)
+}
+
+/// Return the function converting the native field from or into the proto field at `path`, named
+/// after the proto field, and the identifier calling it with the span of the native type.
+///
+/// The conversion traits are bounds of this function, so a missing implementation is reported on
+/// the native field type, with the name of the proto field and the missing trait.
+fn field_fn(path: &[syn::Ident], ty: &syn::Type) -> (syn::Ident, syn::Ident) {
+    let mut name = String::from("proto_field");
+    for segment in path {
+        name.push('_');
+        name.push_str(segment.to_string().trim_start_matches("r#"));
+    }
+    let name = syn::Ident::new(&name, Span::call_site());
+    let mut call = name.clone();
+    call.set_span(ty.span());
+    (name, call)
+}
+
+/// Return the path of the proto field a native field is converted from, or `None` if the
+/// native field is flattened or skipped.
+fn proto_path(field: &Field) -> Option<Vec<syn::Ident>> {
+    if field.attrs.flatten() || field.attrs.skip() {
+        return None;
+    }
+    match field.attrs.from() {
+        Some(path) => Some(path.to_vec()),
+        None => Some(field.name.iter().cloned().collect()),
+    }
+}
+
+/// Return the place expression of the field at `path` in the nested messages of `proto`,
+/// creating the missing messages.
+fn nested_place(proto: TokenStream, path: &[syn::Ident], krate: &syn::Path) -> TokenStream {
+    let (first, rest) = path.split_first().expect("a proto path is never empty");
+    rest.iter().fold(
+        quote!(#proto.#first),
+        |place, segment| quote!(#krate::MessageField::message_mut(&mut #place).#segment),
+    )
+}
+
+fn from_native_enum(data: &[Variant], cont: &Container) -> TokenStream {
+    let native = &cont.ident;
+    let proto = cont.attrs.src();
+    let krate = cont.attrs.crate_path();
+    // The unit variants of an enum with other variants are the ones of a oneof, holding an empty
+    // message or `()`.
+    let oneof = cont.unit_variants().is_none();
     let arm = data.iter().map(|variant| {
         let variant_ident = &variant.ident;
         // TODO: Tuple and struct might be unreachable state.
         match variant.style {
+            Style::Unit if oneof => {
+                quote! {
+                    #native::#variant_ident => #proto::#variant_ident({
+                        fn empty<P: #krate::EmptyMessage>() -> P {
+                            P::default()
+                        }
+                        empty()
+                    })
+                }
+            }
             Style::Unit => {
                 quote! {
-                    #native::#variant_ident => Self::#variant_ident
+                    #native::#variant_ident => #proto::#variant_ident
                 }
             }
-            Style::Newtype => {
+            Style::Newtype if variant.attrs.fields().is_none() => {
+                let (convert_fn, call) = variant_fn(variant);
+                let call = quote_spanned!(call.span()=> #call(__field0));
                 quote! {
-                    #native::#variant_ident(__field0) => Self::#variant_ident(__field0.into_proto())
+                    #native::#variant_ident(__field0) => #proto::#variant_ident({
+                        fn #convert_fn<N, P: #krate::FromNative<N>>(value: N) -> P {
+                            P::from_native(value)
+                        }
+                        #call
+                    })
                 }
             }
-            Style::Tuple => {
+            // The fields are the ones of the message held by the proto variant, given by
+            // `prost_convert(fields(...))`.
+            Style::Newtype | Style::Tuple => {
+                let message = variant.attrs.src().expect("checked by `check_attributes`");
                 let field_names = (0..variant.fields.len())
                     .map(|i| syn::Ident::new(&format!("__field{}", i), Span::call_site()));
                 let field_names2 = field_names.clone();
+                let fields = variant.fields.iter().map(|field| {
+                    let name = field.name.as_ref().expect("tuple fields are named by `fields`");
+                    let convert = from_native_field(field, std::slice::from_ref(name), &cont.attrs, None);
+                    quote!(#name: #convert)
+                });
+                let rest = variant
+                    .attrs
+                    .proto_default()
+                    .then(|| quote!(..std::default::Default::default()));
                 quote! {
-                    #native::#variant_ident(#(#field_names),*) => Self::#variant_ident(#(#field_names2),*)
+                    #native::#variant_ident(#(#field_names),*) => {
+                        // The members of the tuple are the positions of the native fields.
+                        let value = (#(#field_names2,)*);
+                        #proto::#variant_ident(#message {
+                            #(#fields,)*
+                            #rest
+                        })
+                    }
                 }
             }
             Style::Struct => {
                 let members = variant.fields.iter().map(|f| &f.name);
                 let members2 = members.clone();
                 quote! {
-                    #native::#variant_ident { #(#members),* } => Self::#variant_ident { #(#members2),* }
+                    #native::#variant_ident { #(#members),* } => #proto::#variant_ident { #(#members2),* }
                 }
             }
         }
@@ -125,68 +623,579 @@ fn from_native_enum(data: &[Variant], native: &syn::Ident) -> TokenStream {
     });
 
     quote!(
-        use prost_convert::IntoProto;
         match value {
             #(#arm),*
         }
     )
 }
 
+/// Return the implementations of `TryFromProtoFields` and `IntoProtoFields`, used when the
+/// struct is flattened into another one.
+fn proto_fields_impl(
+    data: &[Field],
+    container: &Container,
+    message: Option<&Message>,
+) -> TokenStream {
+    let native = &container.ident;
+    let attrs = &container.attrs;
+    let proto = attrs.src();
+    let krate = attrs.crate_path();
+    let try_from_proto_fields = validated(
+        try_from_proto_body_struct(data, attrs, message, ProtoValue::Fields),
+        attrs,
+    );
+
+    let fields = data
+        .iter()
+        .filter(|field| !field.attrs.skip())
+        .map(|field| {
+            let member = &field.member;
+            match proto_path(field) {
+                Some(path) => {
+                    let place = nested_place(quote!(proto), &path, krate);
+                    let convert = from_native_field(field, &path, attrs, message);
+                    quote!(#place = #convert;)
+                }
+                None => quote!(
+                    #krate::IntoProtoFields::into_proto_fields(value.#member, &mut *proto);
+                ),
+            }
+        });
+    let set = attrs
+        .set()
+        .iter()
+        .map(|(name, expr)| quote!(proto.#name = #expr;));
+
+    let mut names = mapped_proto_fields(data, attrs);
+    names.dedup();
+    let flattened = data
+        .iter()
+        .filter(|field| field.attrs.flatten())
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    let proto_fields = |trait_: TokenStream| {
+        quote!(
+            const PROTO_FIELDS: #krate::ProtoFields = #krate::ProtoFields {
+                names: &[#(#names),*],
+                flattened: &[#(<#flattened as #krate::#trait_<#proto>>::PROTO_FIELDS),*],
+            };
+        )
+    };
+    let try_from_proto_fields_const = proto_fields(quote!(TryFromProtoFields));
+    let into_proto_fields_const = proto_fields(quote!(IntoProtoFields));
+
+    quote!(
+        impl #krate::TryFromProtoFields<#proto> for #native {
+            #try_from_proto_fields_const
+
+            fn try_from_proto_fields(
+                value: &mut #proto,
+            ) -> std::result::Result<Self, #krate::ProstConvertError> {
+                #try_from_proto_fields
+            }
+        }
+
+        impl #krate::IntoProtoFields<#proto> for #native {
+            #into_proto_fields_const
+
+            fn into_proto_fields(self, proto: &mut #proto) {
+                let value = self;
+                #(#fields)*
+                #(#set)*
+            }
+        }
+    )
+}
+
+/// Return the names of the proto fields converted by the fields of a struct, not by its flattened
+/// fields.
+fn mapped_proto_fields(data: &[Field], attrs: &Attributes) -> Vec<String> {
+    let mut names = data
+        .iter()
+        .filter_map(proto_path)
+        .map(|path| path[0].to_string())
+        .chain(attrs.set().iter().map(|(name, _)| name.to_string()))
+        .map(|name| name.trim_start_matches("r#").to_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Return the proto fields left to the flattened fields of a struct.
+///
+/// Without a manifest the fields of the message are unknown, and `None` is returned.
+fn flattened_proto_fields(
+    data: &[Field],
+    attrs: &Attributes,
+    message: Option<&Message>,
+) -> Option<Vec<String>> {
+    let mapped = mapped_proto_fields(data, attrs);
+    let names = message?
+        .field_names()
+        .filter(|name| !mapped.iter().any(|mapped| mapped == name))
+        .map(str::to_owned)
+        .collect();
+    Some(names)
+}
+
+/// Return the identifiers of the proto fields left to the flattened fields of a struct.
+fn flattened_proto_idents(
+    data: &[Field],
+    attrs: &Attributes,
+    message: Option<&Message>,
+) -> Option<Vec<syn::Ident>> {
+    let names = flattened_proto_fields(data, attrs, message)?;
+    let idents = names
+        .iter()
+        .map(|name| {
+            syn::parse_str(name).unwrap_or_else(|_| syn::Ident::new_raw(name, Span::call_site()))
+        })
+        .collect();
+    Some(idents)
+}
+
+/// Return the assertion that the proto fields left to the flattened fields of a struct are
+/// converted by them, evaluated when the crate is checked.
+fn flattened_fields_check(
+    data: &[Field],
+    attrs: &Attributes,
+    message: Option<&Message>,
+) -> Option<TokenStream> {
+    let flattened = data
+        .iter()
+        .filter(|field| field.attrs.flatten())
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    if flattened.is_empty() || (attrs.allow_unmapped() && attrs.proto_default()) {
+        return None;
+    }
+    let krate = attrs.crate_path();
+    let proto = attrs.src();
+    let trait_ = if attrs.allow_unmapped() {
+        quote!(IntoProtoFields)
+    } else {
+        quote!(TryFromProtoFields)
+    };
+    let checks = flattened_proto_fields(data, attrs, message)?
+        .into_iter()
+        .map(|name| {
+            let error = format!("the proto field `{name}` is not mapped");
+            quote_spanned!(proto.span()=>
+                assert!(
+                    #(<#flattened as #krate::#trait_<#proto>>::PROTO_FIELDS.contains(#name))||*,
+                    #error
+                );
+            )
+        });
+    Some(quote!(
+        const _: () = {
+            #(#checks)*
+        };
+    ))
+}
+
 /// return the body of the `try_from_proto` function.
-fn try_from_proto_body(container: &Container) -> TokenStream {
-    match &container.data {
-        Data::Enum(data) => try_from_proto_body_enum(data, &container.attrs),
-        Data::Struct(data) => try_from_proto_body_struct(data),
+fn try_from_proto_body(container: &Container, message: Option<&Message>) -> TokenStream {
+    let krate = container.attrs.crate_path();
+    let body = match &container.data {
+        Data::Enum(data) => try_from_proto_body_enum(data, container),
+        Data::Struct(data) => {
+            try_from_proto_body_struct(data, &container.attrs, message, ProtoValue::Owned)
+        }
+    };
+
+    let body = validated(body, &container.attrs);
+
+    // Proto enums can't be recursive, only messages and oneofs need to track the nesting depth.
+    if container.unit_variants().is_some() {
+        return body;
     }
+
+    quote!(
+        let _depth = #krate::DepthGuard::enter()?;
+        #body
+    )
 }
 
-fn try_from_proto_body_struct(data: &[Field]) -> TokenStream {
-    let fields = data.iter().map(|field| {
-        let name = &field.name;
-        // If the native field is an option we don't to flat the proto one.
-        if inner_ty("Option", field.ty).is_some() {
+/// Reject semantically invalid values once every field has been converted.
+fn validated(body: TokenStream, attrs: &Attributes) -> TokenStream {
+    let Some(validate) = attrs.validate() else {
+        return body;
+    };
+    let krate = attrs.crate_path();
+    quote!(
+        let native: std::result::Result<Self, #krate::ProstConvertError> = { #body };
+        let native = native?;
+        #validate(&native)
+            .map_err(|err| #krate::ProstConvertError::ValidationError(err.into()))?;
+        std::result::Result::Ok(native)
+    )
+}
+
+/// How the proto value is given to the generated conversion.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProtoValue {
+    /// `value` is the proto struct, which is destructured.
+    Owned,
+    /// `value` is a `&mut` to the proto struct, whose fields are taken.
+    Fields,
+}
+
+fn try_from_proto_body_struct(
+    data: &[Field],
+    attrs: &Attributes,
+    message: Option<&Message>,
+    mode: ProtoValue,
+) -> TokenStream {
+    let krate = attrs.crate_path();
+    let locals = data
+        .iter()
+        .map(|field| local_ident(field.name.as_ref()))
+        .collect::<Vec<_>>();
+    let members = data.iter().map(|field| &field.member).collect::<Vec<_>>();
+    let proto = attrs.src();
+    let paths = data.iter().map(proto_path).collect::<Vec<_>>();
+
+    // The proto fields read by the native struct, and the nested messages in which a field is
+    // read.
+    let mut roots: Vec<&syn::Ident> = Vec::new();
+    let mut prefixes: Vec<&[syn::Ident]> = Vec::new();
+    for path in paths.iter().flatten() {
+        if !roots.contains(&&path[0]) {
+            roots.push(&path[0]);
+        }
+        for len in 1..path.len() {
+            if !prefixes.contains(&&path[..len]) {
+                prefixes.push(&path[..len]);
+            }
+        }
+    }
+    let root_locals = roots
+        .iter()
+        .map(|root| path_ident("__proto", &[(*root).clone()]))
+        .collect::<Vec<_>>();
+
+    // Flattened fields take their proto fields first, the others are read from what is left.
+    let flattened = data
+        .iter()
+        .zip(&paths)
+        .zip(&locals)
+        .filter(|((field, _), _)| field.attrs.flatten())
+        .map(|(_, local)| {
+            let value = match mode {
+                ProtoValue::Owned => quote!(&mut value),
+                ProtoValue::Fields => quote!(value),
+            };
+            quote!(
+                let #local = errors.collect(
+                    #krate::TryFromProtoFields::try_from_proto_fields(#value)
+                )?;
+            )
+        })
+        .collect::<Vec<_>>();
+    let destructure = match mode {
+        ProtoValue::Owned => {
+            // The proto struct is destructured exhaustively, so that a new proto field has to be
+            // mapped.
+            let set = attrs.set().iter().map(|(name, _)| name);
+            let mut unmapped = Vec::new();
+            let rest = if attrs.allow_unmapped() {
+                Some(quote!(..))
+            } else if flattened.is_empty() {
+                None
+            } else if let Some(names) = flattened_proto_idents(data, attrs, message) {
+                // The fields left to the flattened structs are listed, so that the destructure
+                // stays exhaustive.
+                unmapped = names;
+                None
+            } else {
+                Some(quote!(..))
+            };
+            let value = (!flattened.is_empty()).then(|| quote!(let mut value = value;));
+            // rustc only names the unmapped fields in a pattern spanned by the user's code.
+            let pattern = quote_spanned!(proto.span()=>
+                #proto { #(#roots: #root_locals,)* #(#set: _,)* #(#unmapped: _,)* #rest }
+            );
             quote!(
-                #name: value
-                        .#name
-                        .map(|field| field.try_into_native())
-                        .transpose()?
+                #value
+                #(#flattened)*
+                let #pattern = value;
             )
-        } else {
-            quote!(#name: value.#name.try_into_native()?)
         }
+        ProtoValue::Fields => quote!(
+            #(#flattened)*
+            #(let #root_locals = std::mem::take(&mut value.#roots);)*
+        ),
+    };
+    let messages = prefixes.iter().map(|prefix| {
+        let local = path_ident("__message", prefix);
+        let value = proto_value(prefix);
+        quote!(let #local = #krate::MessageField::into_message(#value);)
+    });
+
+    let converted = data
+        .iter()
+        .zip(&paths)
+        .zip(&locals)
+        .filter_map(|((field, path), local)| {
+            if field.attrs.skip() {
+                return Some(quote!(
+                    let #local = std::option::Option::Some(std::default::Default::default());
+                ));
+            }
+            let value = proto_value(path.as_ref()?);
+            let convert = try_from_proto_struct_field(field, path.as_ref()?, attrs, message, value);
+            Some(quote!(let #local = errors.collect(#convert)?;))
+        });
+
+    // Every field is converted even if one failed, so that all errors can be reported at once.
+    // Each field is `None` if its conversion failed.
+    let all_converted = (!data.is_empty()).then(|| {
+        quote!(
+            let (#(std::option::Option::Some(#locals),)*) = (#(#locals,)*) else {
+                return std::result::Result::Err(errors.into_error());
+            };
+        )
     });
 
     quote!(
-        use prost_convert::TryIntoNative;
+        let mut errors = #krate::ErrorCollector::new();
+        #destructure
+        #(#messages)*
+        #(#converted)*
+        #all_converted
         std::result::Result::Ok(Self {
-            #(#fields),*
+            #(#members: #locals),*
         })
     )
 }
 
-fn try_from_proto_body_enum(data: &[Variant], attrs: &Attributes) -> TokenStream {
+/// Return the local variable named after a proto path, e.g. `__message_7network_4host`.
+///
+/// Each segment is prefixed by its length, so that two paths never give the same name, e.g.
+/// `a_b.c` and `a.b_c`. The prefixes of the locals are distinct for the same reason, and the
+/// locals are hygienic so that they can't be named by the code of the user.
+fn path_ident(prefix: &str, path: &[syn::Ident]) -> syn::Ident {
+    let mut name = prefix.to_owned();
+    for segment in path {
+        let segment = segment.to_string();
+        let segment = segment.trim_start_matches("r#");
+        name.push_str(&format!("_{}{segment}", segment.len()));
+    }
+    syn::Ident::new(&name, Span::mixed_site())
+}
+
+/// Return the expression of the proto field at `path`, once the proto struct is destructured and
+/// the nested messages are unwrapped.
+fn proto_value(path: &[syn::Ident]) -> TokenStream {
+    match path {
+        [root] => {
+            let local = path_ident("__proto", std::slice::from_ref(root));
+            quote!(#local)
+        }
+        [parent @ .., field] => {
+            let local = path_ident("__message", parent);
+            quote!(#local.#field)
+        }
+        [] => unreachable!("a proto path is never empty"),
+    }
+}
+
+/// Return the local variable holding the proto value of a field, and then its converted value.
+fn local_ident(name: Option<&syn::Ident>) -> syn::Ident {
+    path_ident(
+        "__field",
+        name.map(std::slice::from_ref).unwrap_or_default(),
+    )
+}
+
+/// Return the expression checking the validation rules of a struct field and converting it.
+/// Its type is `Result<T, ProstConvertError>`.
+fn try_from_proto_struct_field(
+    field: &Field,
+    path: &[syn::Ident],
+    attrs: &Attributes,
+    message: Option<&Message>,
+    value: TokenStream,
+) -> TokenStream {
+    let krate = attrs.crate_path();
+    let convert = if empty_as_none(field, attrs) {
+        let convert = try_from_proto_field(field, path, quote!(field), krate);
+        quote!({
+            let field = #value;
+            if #krate::ZeroValue::is_zero_value(&field) {
+                std::result::Result::Ok(std::option::Option::None)
+            } else {
+                #convert.map(std::option::Option::Some)
+            }
+        })
+    } else if always_set(field, message) {
+        let convert = try_from_proto_field(field, path, value.clone(), krate);
+        quote!(#convert.map(std::option::Option::Some))
+    } else if inner_ty("Option", field.ty).is_some() {
+        // If the native field is an option we don't to flat the proto one.
+        let convert = try_from_proto_field(field, path, quote!(field), krate);
+        quote!(#value.map(|field| #convert).transpose())
+    } else {
+        try_from_proto_field(field, path, value.clone(), krate)
+    };
+
+    // A field breaking a rule is not converted.
+    field
+        .attrs
+        .rules()
+        .iter()
+        .rev()
+        .fold(convert, |convert, rule| {
+            let check = check_rule(rule, path, &value, krate);
+            quote!(#check.and_then(|()| #convert))
+        })
+}
+
+/// Return the expression checking a validation rule on the proto field `value` at `path`, which
+/// names the field in the violation, such as `network.host`.
+fn check_rule(
+    rule: &Rule,
+    path: &[syn::Ident],
+    value: &TokenStream,
+    krate: &syn::Path,
+) -> TokenStream {
+    let name = path
+        .iter()
+        .map(|segment| segment.to_string().trim_start_matches("r#").to_owned())
+        .collect::<Vec<_>>()
+        .join(".");
+    match rule {
+        Rule::MinLen(min) => quote!(#krate::validate::min_len(#name, &#value, #min)),
+        Rule::MaxLen(max) => quote!(#krate::validate::max_len(#name, &#value, #max)),
+        Rule::MaxItems(max) => quote!(#krate::validate::max_items(#name, &#value, #max)),
+        Rule::Range(range, repr) => {
+            quote!(#krate::validate::range(#name, &#value, #range, #repr))
+        }
+        Rule::Pattern(pattern) => {
+            // Spanned so that the error of a missing `regex` feature points at the pattern.
+            let mut krate = krate.clone();
+            for segment in &mut krate.segments {
+                segment.ident.set_span(pattern.span());
+            }
+            quote_spanned!(pattern.span()=>
+                #krate::__validate_pattern!(#name, &#value, #pattern)
+            )
+        }
+    }
+}
+
+/// Return the expression converting the proto `value` at `path` into the (non optional) native
+/// field.
+fn try_from_proto_field(
+    field: &Field,
+    path: &[syn::Ident],
+    value: TokenStream,
+    krate: &syn::Path,
+) -> TokenStream {
+    if let Some(with) = field.attrs.with() {
+        return quote_spanned!(with.span()=>
+            #krate::WithField::try_into_proto(#value).and_then(#with::try_from_proto)
+        );
+    }
+    let (convert_fn, call) = field_fn(path, field.ty);
+    let call = quote_spanned!(call.span()=> #call(#value));
+    let (bound, convert) = match field.attrs.duplicates() {
+        Duplicates::Dedup => (
+            quote!(#krate::TryFromProto<P>),
+            quote!(N::try_from_proto(value)),
+        ),
+        Duplicates::Reject => (
+            quote!(#krate::TryFromProtoUnique<P>),
+            quote!(N::try_from_proto_unique(value)),
+        ),
+    };
+    quote!({
+        fn #convert_fn<N: #bound, P>(
+            value: P,
+        ) -> std::result::Result<N, #krate::ProstConvertError> {
+            #convert
+        }
+        #call
+    })
+}
+
+fn try_from_proto_body_enum(data: &[Variant], container: &Container) -> TokenStream {
+    let attrs = &container.attrs;
+    let krate = attrs.crate_path();
+    let oneof = container.unit_variants().is_none();
     let variants = data.iter().map(|variant| {
         let variant_ident = &variant.ident;
         let proto = attrs.src();
 
         match variant.style {
+            // The empty message of a oneof variant has nothing to convert.
+            Style::Unit if oneof => {
+                quote! {
+                    #proto::#variant_ident(message) => {
+                        fn empty<P: #krate::EmptyMessage>(_: P) {}
+                        empty(message);
+                        Self::#variant_ident
+                    }
+                }
+            }
             Style::Unit => {
                 quote! {
                     #proto::#variant_ident => Self::#variant_ident
                 }
             }
-            Style::Newtype => {
-                // In case the proto field is an () and native one unit, this won't work. Native must also be ()
+            Style::Newtype if variant.attrs.fields().is_none() => {
+                let (convert_fn, call) = variant_fn(variant);
+                let call = quote_spanned!(call.span()=> #call(__field0));
                 quote! {
-                    #proto::#variant_ident(__field0) => Self::#variant_ident(__field0.try_into_native()?)
+                    #proto::#variant_ident(__field0) => Self::#variant_ident({
+                        fn #convert_fn<N: #krate::TryFromProto<P>, P>(
+                            value: P,
+                        ) -> std::result::Result<N, #krate::ProstConvertError> {
+                            N::try_from_proto(value)
+                        }
+                        #call?
+                    })
                 }
             }
-            Style::Tuple => {
-                let field_names = (0..variant.fields.len())
-                    .map(|i| syn::Ident::new(&format!("__field{}", i), Span::call_site()));
-                let field_names2 = field_names.clone();
+            Style::Newtype | Style::Tuple => {
+                let message = variant.attrs.src().expect("checked by `check_attributes`");
+                let names = variant
+                    .fields
+                    .iter()
+                    .map(|field| field.name.as_ref().expect("tuple fields are named by `fields`"))
+                    .collect::<Vec<_>>();
+                let locals = names
+                    .iter()
+                    .map(|name| local_ident(Some(name)))
+                    .collect::<Vec<_>>();
+                let fields = variant.fields.iter().zip(&names).zip(&locals).map(
+                    |((field, name), local)| {
+                        try_from_proto_struct_field(
+                            field,
+                            std::slice::from_ref(name),
+                            attrs,
+                            None,
+                            quote!(#local),
+                        )
+                    },
+                );
+                // The message is destructured exhaustively, like a proto struct.
+                let rest = (variant.attrs.allow_unmapped() || variant.attrs.proto_default())
+                    .then(|| quote!(..));
+                let pattern = quote_spanned!(message.span()=>
+                    #message { #(#names: #locals,)* #rest }
+                );
                 quote! {
-                    #proto::#variant_ident(#(#field_names),*) => Self::#variant_ident(#(#field_names2),*)
+                    #proto::#variant_ident(message) => {
+                        let mut errors = #krate::ErrorCollector::new();
+                        let #pattern = message;
+                        #(let #locals = errors.collect(#fields)?;)*
+                        let (#(std::option::Option::Some(#locals),)*) = (#(#locals,)*) else {
+                            return std::result::Result::Err(errors.into_error());
+                        };
+                        Self::#variant_ident(#(#locals),*)
+                    }
                 }
             }
             // Style::struct can't be generated from Prost. If the native variant is `Style::struct` we make the asumption
@@ -201,31 +1210,46 @@ fn try_from_proto_body_enum(data: &[Variant], attrs: &Attributes) -> TokenStream
         }
     });
 
+    // The proto variants missing from the native enum can't be converted.
+    let unmapped = attrs.allow_unmapped().then(|| {
+        quote!(
+            #[allow(unreachable_patterns)]
+            _ => return std::result::Result::Err(
+                #krate::ProstConvertError::UnknownEnumValue(value as i32)
+            ),
+        )
+    });
+
     quote!(
 
-        use prost_convert::TryIntoNative;
         std::result::Result::Ok(
             match value {
-                #(#variants),*
+                #(#variants,)*
+                #unmapped
             }
         )
     )
 }
 
-// we assume that the inner struct got the same name as the Wrapped one in lower case.
-fn wrapper_struct_impl(wrapper: &syn::Path, native: &syn::Ident) -> TokenStream {
-    let wrapper_inner_field = ident_to_snake_case(native);
+/// Return the conversions between the native type and the proto struct wrapping it in a single
+/// field. The field can be an `Option`, like the field of a prost oneof.
+fn wrapper_struct_impl(
+    wrapper: &syn::Path,
+    wrapper_inner_field: &syn::Ident,
+    native: &syn::Ident,
+    krate: &syn::Path,
+) -> TokenStream {
     quote!(
-        impl prost_convert::TryFromProto<#wrapper> for #native {
-            fn try_from_proto(value: #wrapper) -> std::result::Result<Self, prost_convert::ProstConvertError> {
-                use prost_convert::TryIntoNative;
+        impl #krate::TryFromProto<#wrapper> for #native {
+            fn try_from_proto(value: #wrapper) -> std::result::Result<Self, #krate::ProstConvertError> {
+                use #krate::TryIntoNative;
                 value.#wrapper_inner_field.try_into_native()
             }
         }
 
-        impl prost_convert::FromNative<#native> for #wrapper {
+        impl #krate::FromNative<#native> for #wrapper {
             fn from_native(value: #native) -> Self {
-                use prost_convert::IntoProto;
+                use #krate::IntoProto;
                 Self {
                     #wrapper_inner_field: value.into_proto(),
                 }
@@ -239,57 +1263,191 @@ fn ident_to_snake_case(ident: &syn::Ident) -> syn::Ident {
     syn::Ident::new(&snake_case, ident.span())
 }
 
+/// Convert an upper camel case name into snake case, like prost does for its fields. An acronym is
+/// one word, e.g. `HTTPRequest` becomes `http_request`.
 fn to_snake_case(value: &str) -> String {
+    let chars = value.chars().collect::<Vec<_>>();
     let mut acc = String::new();
-    let mut prev = '_';
-    for ch in value.to_string().chars() {
-        if ch.is_uppercase() && prev != '_' {
-            acc.push('_');
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1);
+            // A word starts after a lower case letter, or at the last capital of an acronym.
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            {
+                acc.push('_');
+            }
         }
-        acc.push(ch);
-        prev = ch;
+        acc.extend(ch.to_lowercase());
     }
-    acc.to_lowercase()
+    acc
 }
 
 fn enum_i32_convertion(variants: &[Variant], container: &Container) -> syn::Result<TokenStream> {
     let native = &container.ident;
+    let krate = container.attrs.crate_path();
+    let (from_native_body, try_from_proto_body) = enum_i32_bodies(variants, container);
 
-    let from_native_arm = variants
-        .iter()
-        .map(|variant| &variant.ident)
-        .enumerate()
-        .map(|(n, ident)| (n as i32, ident))
-        .map(|(n, ident)| quote!(#n => std::result::Result::Ok(#native::#ident)));
+    Ok(quote!(
+
+        impl #krate::FromNative<#native> for i32 {
+            fn from_native(value: #native) -> Self {
+                #from_native_body
+            }
+        }
+
+        impl #krate::TryFromProto<i32> for #native {
+            fn try_from_proto(value: i32) -> std::result::Result<Self, #krate::ProstConvertError> {
+                #try_from_proto_body
+            }
+        }
+
+    ))
+}
+
+/// Return the bodies of the `from_native` and `try_from_proto` functions converting an enum with
+/// unit variants from and into `i32`.
+fn enum_i32_bodies(variants: &[Variant], container: &Container) -> (TokenStream, TokenStream) {
+    let native = &container.ident;
+    let proto = container.attrs.src();
+    let krate = container.attrs.crate_path();
+
+    // Prost enums are converted from and into `i32` with their discriminant.
+    let from_native_arm = variants.iter().map(|variant| &variant.ident).map(
+        |ident| quote!(value if value == #proto::#ident as i32 => std::result::Result::Ok(#native::#ident)),
+    );
 
     let try_from_proto_arm = variants
         .iter()
         .map(|variant| &variant.ident)
-        .enumerate()
-        .map(|(n, ident)| (n as i32, ident))
-        .map(|(n, ident)| quote!(#native::#ident => #n));
+        .map(|ident| quote!(#native::#ident => #proto::#ident as i32));
 
-    // FIXME: wrong error variant. panic or add a variant to the error?
-    Ok(quote!(
-
-        impl prost_convert::FromNative<#native> for i32 {
-            fn from_native(value: #native) -> Self {
+    (
+        quote!(
+            match value {
+                #(#try_from_proto_arm,)*
+            }
+        ),
+        // Prost messages hold enums as `i32`, this is the conversion which must be validated.
+        validated(
+            quote!(
                 match value {
-                    #(#try_from_proto_arm,)*
+                    #(#from_native_arm,)*
+                    _ => std::result::Result::Err(#krate::ProstConvertError::UnknownEnumValue(value)),
                 }
+            ),
+            &container.attrs,
+        ),
+    )
+}
+
+/// Return the functions converting the foreign type `remote` from and into the proto type, for
+/// the local mirror of its definition in `container`.
+///
+/// The foreign type is converted into the mirror, which is converted like any native type, so
+/// that the fields of the mirror are checked against the ones of the foreign type.
+fn expand_remote(container: &Container, remote: &syn::Path) -> syn::Result<TokenStream> {
+    check_attributes(container)?;
+    let message = proto_message(container)?;
+
+    let mirror = &container.ident;
+    let vis = &container.vis;
+    let krate = container.attrs.crate_path();
+    let (proto, from_native_body, try_from_proto_body) = match container.unit_variants() {
+        Some(variants) => {
+            let (from_native_body, try_from_proto_body) = enum_i32_bodies(variants, container);
+            (quote!(i32), from_native_body, try_from_proto_body)
+        }
+        None => {
+            let proto = container.attrs.src();
+            (
+                quote!(#proto),
+                from_native_body(container, message.as_ref()),
+                try_from_proto_body(container, message.as_ref()),
+            )
+        }
+    };
+
+    // The foreign type is named through an alias, which can be used in patterns and struct
+    // expressions even if it has generic arguments.
+    let remote_alias = quote!(__ProstConvertRemote);
+    let into_mirror = remote_mapping(container, &remote_alias, &quote!(Self));
+    let from_mirror = remote_mapping(container, &quote!(Self), &remote_alias);
+
+    Ok(quote!(
+        impl #mirror {
+            #[doc = "Convert the foreign native type into its proto type."]
+            #vis fn from_native(value: #remote) -> #proto {
+                #[allow(non_camel_case_types)]
+                type #remote_alias = #remote;
+                Self::__prost_convert_from_native(#into_mirror)
+            }
+
+            #[doc = "Convert the proto type into the foreign native type."]
+            #vis fn try_from_proto(
+                value: #proto,
+            ) -> std::result::Result<#remote, #krate::ProstConvertError> {
+                #[allow(non_camel_case_types)]
+                type #remote_alias = #remote;
+                let value = Self::__prost_convert_try_from_proto(value)?;
+                std::result::Result::Ok(#from_mirror)
+            }
+
+            fn __prost_convert_from_native(value: Self) -> #proto {
+                #from_native_body
+            }
+
+            fn __prost_convert_try_from_proto(
+                value: #proto,
+            ) -> std::result::Result<Self, #krate::ProstConvertError> {
+                #try_from_proto_body
             }
         }
+    ))
+}
 
-        impl prost_convert::TryFromProto<i32> for #native {
-            fn try_from_proto(value: i32) -> std::result::Result<Self, prost_convert::ProstConvertError> {
+/// Return the expression moving the fields of `value`, of type `from`, into the type `to`. Both
+/// types have the fields (or the variants) of `container`.
+fn remote_mapping(container: &Container, from: &TokenStream, to: &TokenStream) -> TokenStream {
+    match &container.data {
+        Data::Struct(fields) => {
+            let members = fields.iter().map(|field| &field.member);
+            let members2 = members.clone();
+            let locals = fields
+                .iter()
+                .map(|field| local_ident(field.name.as_ref()))
+                .collect::<Vec<_>>();
+            quote!({
+                let #from { #(#members: #locals),* } = value;
+                #to { #(#members2: #locals),* }
+            })
+        }
+        Data::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let fields = match variant.style {
+                    Style::Unit => TokenStream::new(),
+                    Style::Newtype | Style::Tuple => {
+                        let field_names = (0..variant.fields.len())
+                            .map(|i| syn::Ident::new(&format!("__field{}", i), Span::call_site()));
+                        quote!((#(#field_names),*))
+                    }
+                    Style::Struct => {
+                        let members = variant.fields.iter().map(|field| &field.name);
+                        quote!({ #(#members),* })
+                    }
+                };
+                quote!(#from::#ident #fields => #to::#ident #fields)
+            });
+            quote!(
                 match value {
-                    #(#from_native_arm,)*
-                    _ => std::result::Result::Err(prost_convert::ProstConvertError::MissingRequiredField),
+                    #(#arms,)*
                 }
-            }
+            )
         }
-
-    ))
+    }
 }
 
 #[cfg(test)]
@@ -300,6 +1458,9 @@ mod test {
         assert_eq!(to_snake_case("Foo"), "foo");
         assert_eq!(to_snake_case("FooBar"), "foo_bar");
         assert_eq!(to_snake_case("FooBarBaz"), "foo_bar_baz");
-        assert_eq!(to_snake_case("FOO"), "f_o_o");
+        assert_eq!(to_snake_case("FOO"), "foo");
+        assert_eq!(to_snake_case("HTTPRequest"), "http_request");
+        assert_eq!(to_snake_case("GetHTTPRequest"), "get_http_request");
+        assert_eq!(to_snake_case("Ipv4Addr"), "ipv4_addr");
     }
 }
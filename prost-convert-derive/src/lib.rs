@@ -8,6 +8,7 @@
 mod attributes;
 mod container;
 mod expand;
+mod manifest;
 mod symbol;
 use expand::expand_derive_prost_convert;
 
@@ -0,0 +1,223 @@
+//! The manifest of the proto structs written by `prost-convert-build`.
+//!
+//! When the build script of the crate emits it, the derive macro knows the fields of the proto
+//! struct, and how prost wraps them.
+
+use std::{cell::RefCell, rc::Rc, time::SystemTime};
+
+use proc_macro2::Span;
+
+/// The environment variable set by `prost-convert-build`.
+const MANIFEST_ENV: &str = "PROST_CONVERT_MANIFEST";
+
+/// How prost wraps the value of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    Plain,
+    Optional,
+    Repeated,
+    Map,
+}
+
+/// A proto message, and the fields of the struct generated for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub proto_name: String,
+    rust_path: Vec<String>,
+    fields: Vec<(String, Presence)>,
+}
+
+impl Message {
+    /// Return how the field `name` is wrapped, or `None` if the message doesn't have it.
+    pub fn presence(&self, name: &str) -> Option<Presence> {
+        let name = name.trim_start_matches("r#");
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, presence)| *presence)
+    }
+
+    /// Return the names of the fields of the message, without the `r#` prefix.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// The messages of a manifest, and the paths of the module in which they are included.
+#[derive(Debug)]
+struct Manifest {
+    /// The paths naming the module of the generated code, e.g. `crate::proto`, `proto` from the
+    /// crate root and `my_crate::proto` from the integration tests.
+    roots: Vec<Vec<String>>,
+    messages: Vec<Message>,
+}
+
+/// A parsed manifest, and the file it was read from.
+struct Cached {
+    path: String,
+    modified: Option<SystemTime>,
+    manifest: Rc<Manifest>,
+}
+
+thread_local! {
+    // The same manifest is used by every derive of a crate, only parse it once.
+    static CACHE: RefCell<Option<Cached>> = const { RefCell::new(None) };
+}
+
+/// Return the message generated as `src`, if the crate has a manifest.
+///
+/// `src` must be the full path of the struct: the module in which the generated code is included,
+/// followed by the path of the struct in it. Since the manifest changes how the fields are
+/// converted, a path which is not in the manifest is an error rather than silently converted
+/// without it.
+pub fn lookup(src: &syn::Path) -> syn::Result<Option<Message>> {
+    let Ok(path) = std::env::var(MANIFEST_ENV) else {
+        return Ok(None);
+    };
+    let manifest = load(&path)?;
+
+    let segments = src
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    let matching = |message: &&Message| {
+        manifest.roots.iter().any(|root| {
+            segments.len() == root.len() + message.rust_path.len()
+                && segments[..root.len()] == root[..]
+                && segments[root.len()..] == message.rust_path[..]
+        })
+    };
+    match manifest.messages.iter().find(matching) {
+        Some(message) => Ok(Some(message.clone())),
+        None => {
+            let roots = manifest
+                .roots
+                .iter()
+                .map(|root| format!("`{}::...`", root.join("::")))
+                .collect::<Vec<_>>();
+            Err(syn::Error::new_spanned(
+                src,
+                format!(
+                    "no proto message at this path in the prost-convert manifest, name it from {}, or use `prost_convert(skip_manifest)` for a proto struct written by hand",
+                    roots.join(", ")
+                ),
+            ))
+        }
+    }
+}
+
+fn load(path: &str) -> syn::Result<Rc<Manifest>> {
+    let error = |err: String| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("invalid prost-convert manifest `{path}`: {err}"),
+        )
+    };
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(cached) = cache.as_ref() {
+            if cached.path == path && cached.modified == modified {
+                return Ok(cached.manifest.clone());
+            }
+        }
+        let content = std::fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let manifest = Rc::new(parse(&content).map_err(error)?);
+        *cache = Some(Cached {
+            path: path.to_owned(),
+            modified,
+            manifest: manifest.clone(),
+        });
+        Ok(manifest)
+    })
+}
+
+fn parse(content: &str) -> Result<Manifest, String> {
+    let mut proto_path = vec!["crate".to_owned(), "proto".to_owned()];
+    let mut crate_name = None;
+    let mut messages: Vec<Message> = Vec::new();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || format!("line {}: `{line}`", n + 1);
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["proto_path", path] => proto_path = path.split("::").map(str::to_owned).collect(),
+            ["crate", name] => crate_name = Some(name.to_owned()),
+            ["message", proto_name, rust_path] => messages.push(Message {
+                proto_name: proto_name.to_owned(),
+                rust_path: rust_path.split("::").map(str::to_owned).collect(),
+                fields: Vec::new(),
+            }),
+            ["field", name, presence] => {
+                let presence = match presence {
+                    "plain" => Presence::Plain,
+                    "optional" => Presence::Optional,
+                    "repeated" => Presence::Repeated,
+                    "map" => Presence::Map,
+                    _ => return Err(invalid()),
+                };
+                messages
+                    .last_mut()
+                    .ok_or_else(invalid)?
+                    .fields
+                    .push((name.to_owned(), presence));
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    // A path from the crate root can be relative, and the integration tests name the crate.
+    let mut roots = vec![proto_path.clone()];
+    if let Some(("crate", module)) = proto_path
+        .split_first()
+        .map(|(first, rest)| (&**first, rest))
+    {
+        roots.push(module.to_vec());
+        if let Some(crate_name) = crate_name {
+            roots.push([&[crate_name][..], module].concat());
+        }
+    }
+    Ok(Manifest { roots, messages })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, Presence};
+
+    #[test]
+    fn test_parse() {
+        let manifest = parse(
+            "# Generated by prost-convert-build.\n\
+             proto_path crate::proto\n\
+             crate my_crate\n\
+             message .foo.User foo::User\n\
+             field name plain\n\
+             field type optional\n\
+             message .foo.User.Address foo::user::Address\n",
+        )
+        .unwrap();
+        let messages = &manifest.messages;
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].presence("name"), Some(Presence::Plain));
+        assert_eq!(messages[0].presence("r#type"), Some(Presence::Optional));
+        assert_eq!(messages[0].presence("age"), None);
+        assert_eq!(messages[1].rust_path, ["foo", "user", "Address"]);
+        assert_eq!(
+            manifest.roots,
+            [
+                vec!["crate", "proto"],
+                vec!["proto"],
+                vec!["my_crate", "proto"]
+            ]
+        );
+        assert!(parse("field name plain").is_err());
+        assert!(parse("message .foo.User foo::User\nfield name maybe").is_err());
+    }
+}
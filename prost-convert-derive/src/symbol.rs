@@ -8,7 +8,30 @@ use syn::{Ident, Path};
 pub struct Symbol(&'static str);
 
 pub const SRC: Symbol = Symbol("src");
+pub const CRATE: Symbol = Symbol("crate");
+pub const REMOTE: Symbol = Symbol("remote");
 pub const WRAPPER: Symbol = Symbol("wrapper");
+pub const WRAPPER_FIELD: Symbol = Symbol("wrapper_field");
+pub const CANONICAL: Symbol = Symbol("canonical");
+pub const ROUNDTRIP_TEST: Symbol = Symbol("roundtrip_test");
+pub const SKIP_MANIFEST: Symbol = Symbol("skip_manifest");
+pub const DUPLICATES: Symbol = Symbol("duplicates");
+pub const EMPTY_AS_NONE: Symbol = Symbol("empty_as_none");
+pub const VALIDATE: Symbol = Symbol("validate");
+pub const MIN_LEN: Symbol = Symbol("min_len");
+pub const MAX_LEN: Symbol = Symbol("max_len");
+pub const PATTERN: Symbol = Symbol("pattern");
+pub const RANGE: Symbol = Symbol("range");
+pub const MAX_ITEMS: Symbol = Symbol("max_items");
+pub const ALLOW_UNMAPPED: Symbol = Symbol("allow_unmapped");
+pub const PROTO_DEFAULT: Symbol = Symbol("proto_default");
+pub const SET: Symbol = Symbol("set");
+pub const FROM: Symbol = Symbol("from");
+pub const FLATTEN: Symbol = Symbol("flatten");
+pub const SKIP: Symbol = Symbol("skip");
+pub const RENAME: Symbol = Symbol("rename");
+pub const WITH: Symbol = Symbol("with");
+pub const FIELDS: Symbol = Symbol("fields");
 
 impl PartialEq<Symbol> for Ident {
     fn eq(&self, word: &Symbol) -> bool {
@@ -16,7 +39,7 @@ impl PartialEq<Symbol> for Ident {
     }
 }
 
-impl<'a> PartialEq<Symbol> for &'a Ident {
+impl PartialEq<Symbol> for &Ident {
     fn eq(&self, word: &Symbol) -> bool {
         *self == word.0
     }
@@ -28,7 +51,7 @@ impl PartialEq<Symbol> for Path {
     }
 }
 
-impl<'a> PartialEq<Symbol> for &'a Path {
+impl PartialEq<Symbol> for &Path {
     fn eq(&self, word: &Symbol) -> bool {
         self.is_ident(word.0)
     }
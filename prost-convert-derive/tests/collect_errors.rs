@@ -0,0 +1,164 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use indexmap::IndexMap;
+use smallvec::SmallVec;
+
+use prost_convert::{ProstConvertError, TryFromProto, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    use std::collections::HashMap;
+
+    pub struct Host {
+        pub name: String,
+        pub addr: String,
+        pub port: u32,
+        pub interfaces: Vec<Interface>,
+        pub routes: HashMap<String, String>,
+        pub gateway: Option<Interface>,
+    }
+
+    pub struct Interface {
+        pub addr: String,
+        pub mtu: u32,
+    }
+
+    pub struct Firewall {
+        pub blocked: Vec<String>,
+        pub routes: HashMap<String, String>,
+        pub ports: Vec<u32>,
+    }
+}
+
+#[derive(Debug, ProstConvert)]
+#[prost_convert(src = "proto::Host")]
+pub struct Host {
+    #[prost_convert(validate(min_len = 1))]
+    name: String,
+    addr: IpAddr,
+    port: u16,
+    interfaces: Vec<Interface>,
+    routes: HashMap<String, IpAddr>,
+    gateway: Interface,
+}
+
+#[derive(Debug, ProstConvert)]
+#[prost_convert(src = "proto::Interface")]
+pub struct Interface {
+    addr: IpAddr,
+    mtu: u16,
+}
+
+#[derive(Debug, ProstConvert)]
+#[prost_convert(src = "proto::Firewall")]
+pub struct Firewall {
+    #[prost_convert(duplicates = "reject")]
+    blocked: HashSet<IpAddr>,
+    routes: IndexMap<String, IpAddr>,
+    ports: SmallVec<[u16; 4]>,
+}
+
+fn valid_interface() -> proto::Interface {
+    proto::Interface {
+        addr: String::from("10.0.0.1"),
+        mtu: 1500,
+    }
+}
+
+fn invalid_interface() -> proto::Interface {
+    proto::Interface {
+        addr: String::from("foo"),
+        mtu: 100_000,
+    }
+}
+
+fn invalid_host() -> proto::Host {
+    proto::Host {
+        name: String::new(),
+        addr: String::from("foo"),
+        port: 100_000,
+        interfaces: vec![valid_interface(), invalid_interface()],
+        routes: HashMap::from([(String::from("default"), String::from("bar"))]),
+        gateway: None,
+    }
+}
+
+#[test]
+fn every_error_is_reported() {
+    let errors = Host::try_from_proto_all(invalid_host()).err().unwrap();
+
+    assert_eq!(errors.len(), 7);
+    assert!(matches!(errors[0], ProstConvertError::InvalidField { .. }));
+    assert!(matches!(errors[1], ProstConvertError::AddrParseError(_)));
+    assert!(matches!(errors[2], ProstConvertError::TryFromIntError(_)));
+    // Errors of nested messages are flattened.
+    assert!(matches!(errors[3], ProstConvertError::AddrParseError(_)));
+    assert!(matches!(errors[4], ProstConvertError::TryFromIntError(_)));
+    assert!(matches!(errors[5], ProstConvertError::AddrParseError(_)));
+    assert!(matches!(errors[6], ProstConvertError::MissingRequiredField));
+}
+
+#[test]
+fn try_into_native_all() {
+    let native: Result<Host, _> = invalid_host().try_into_native_all();
+
+    assert_eq!(native.err().unwrap().len(), 7);
+}
+
+#[test]
+fn first_error_is_returned_by_default() {
+    let native: Result<Host, _> = invalid_host().try_into_native();
+
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::InvalidField { .. }
+    ));
+}
+
+#[test]
+fn valid_message() {
+    let proto = proto::Host {
+        name: String::from("foo"),
+        addr: String::from("127.0.0.1"),
+        port: 80,
+        interfaces: vec![valid_interface()],
+        routes: HashMap::new(),
+        gateway: Some(valid_interface()),
+    };
+
+    assert!(Host::try_from_proto_all(proto).is_ok());
+}
+
+#[test]
+fn vector_errors() {
+    let proto = vec![invalid_interface(), valid_interface(), invalid_interface()];
+    let errors = Vec::<Interface>::try_from_proto_all(proto).err().unwrap();
+
+    assert_eq!(errors.len(), 4);
+}
+
+#[test]
+fn collection_errors() {
+    let proto = proto::Firewall {
+        blocked: vec![
+            String::from("10.0.0.1"),
+            String::from("foo"),
+            String::from("10.0.0.1"),
+        ],
+        routes: HashMap::from([
+            (String::from("default"), String::from("bar")),
+            (String::from("local"), String::from("baz")),
+        ]),
+        ports: vec![80, 100_000, 200_000],
+    };
+    let errors = Firewall::try_from_proto_all(proto).err().unwrap();
+
+    assert_eq!(errors.len(), 6);
+    assert!(matches!(errors[0], ProstConvertError::AddrParseError(_)));
+    assert!(matches!(errors[1], ProstConvertError::DuplicateElement));
+    assert!(matches!(errors[2], ProstConvertError::AddrParseError(_)));
+    assert!(matches!(errors[3], ProstConvertError::AddrParseError(_)));
+    assert!(matches!(errors[4], ProstConvertError::TryFromIntError(_)));
+    assert!(matches!(errors[5], ProstConvertError::TryFromIntError(_)));
+}
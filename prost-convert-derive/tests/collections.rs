@@ -0,0 +1,144 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use indexmap::{IndexMap, IndexSet};
+use prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+use smallvec::SmallVec;
+
+pub mod proto {
+    use std::collections::HashMap;
+
+    #[derive(PartialEq, Debug)]
+    pub struct Permissions {
+        pub roles: Vec<String>,
+        pub tags: Vec<String>,
+        pub ports: Vec<u32>,
+        pub history: Vec<String>,
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct StrictPermissions {
+        pub roles: Vec<String>,
+        pub tags: Vec<String>,
+        pub ports: Vec<u32>,
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Ordered {
+        pub tags: Vec<String>,
+        pub labels: HashMap<String, String>,
+        pub ports: Vec<u32>,
+    }
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::Permissions")]
+pub struct Permissions {
+    roles: HashSet<String>,
+    tags: BTreeSet<String>,
+    ports: HashSet<u16>,
+    history: VecDeque<String>,
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::StrictPermissions")]
+pub struct StrictPermissions {
+    #[prost_convert(duplicates = "reject")]
+    roles: HashSet<String>,
+    #[prost_convert(duplicates = "dedup")]
+    tags: BTreeSet<String>,
+    #[prost_convert(duplicates = "reject")]
+    ports: BTreeSet<u16>,
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::Ordered")]
+pub struct Ordered {
+    #[prost_convert(duplicates = "reject")]
+    tags: IndexSet<String>,
+    labels: IndexMap<String, String>,
+    ports: SmallVec<[u16; 4]>,
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn sets() {
+    let native = Permissions {
+        roles: HashSet::from([String::from("admin"), String::from("user")]),
+        tags: BTreeSet::from([String::from("a"), String::from("b")]),
+        ports: HashSet::from([80, 443]),
+        history: VecDeque::from([String::from("login"), String::from("logout")]),
+    };
+    let proto: proto::Permissions = native.clone().into_proto();
+
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn duplicates_are_removed_by_default() {
+    let proto = proto::Permissions {
+        roles: strings(&["admin", "admin"]),
+        tags: strings(&["b", "a", "b"]),
+        ports: vec![80, 80],
+        history: strings(&["login", "login"]),
+    };
+    let native: Permissions = proto.try_into_native().unwrap();
+
+    assert_eq!(native.roles, HashSet::from([String::from("admin")]));
+    assert_eq!(
+        native.tags,
+        BTreeSet::from([String::from("a"), String::from("b")])
+    );
+    assert_eq!(native.ports, HashSet::from([80]));
+    // Only sets remove duplicates.
+    assert_eq!(native.history.len(), 2);
+}
+
+#[test]
+fn duplicates_are_rejected() {
+    let proto = proto::StrictPermissions {
+        roles: strings(&["admin", "user"]),
+        tags: strings(&["a", "a"]),
+        ports: vec![80, 443],
+    };
+    let native: StrictPermissions = proto.try_into_native().unwrap();
+    assert_eq!(native.tags.len(), 1);
+
+    let proto = proto::StrictPermissions {
+        roles: strings(&["admin", "admin"]),
+        tags: vec![],
+        ports: vec![],
+    };
+    let native: Result<StrictPermissions, _> = proto.try_into_native();
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::DuplicateElement
+    ));
+
+    let proto = proto::StrictPermissions {
+        roles: vec![],
+        tags: vec![],
+        ports: vec![443, 443],
+    };
+    let native: Result<StrictPermissions, _> = proto.try_into_native();
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::DuplicateElement
+    ));
+}
+
+#[test]
+fn ordered_collections() {
+    let native = Ordered {
+        tags: IndexSet::from([String::from("b"), String::from("a")]),
+        labels: IndexMap::from([(String::from("env"), String::from("prod"))]),
+        ports: SmallVec::from_slice(&[443, 80]),
+    };
+    let proto: proto::Ordered = native.clone().into_proto();
+    assert_eq!(proto.tags, strings(&["b", "a"]));
+
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
@@ -0,0 +1,105 @@
+use prost_convert_derive::ProstConvert;
+
+// An umbrella crate re-exporting prost-convert.
+mod sdk {
+    pub use prost_convert;
+}
+
+use sdk::prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Network {
+        pub host: String,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct Server {
+        pub name: String,
+        pub network: Option<Network>,
+        pub port: Option<u32>,
+        pub status: i32,
+        pub contact: Option<Contact>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    #[repr(i32)]
+    pub enum Status {
+        Up = 0,
+        Down = 1,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub enum Contact {
+        Email(String),
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, ProstConvert)]
+#[prost_convert(src = "proto::Status", crate = "sdk::prost_convert")]
+enum Status {
+    Up,
+    Down,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Contact", crate = "sdk::prost_convert")]
+enum Contact {
+    Email(String),
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Server", crate = "sdk::prost_convert")]
+struct Server {
+    #[prost_convert(validate(min_len = 1))]
+    name: String,
+    #[prost_convert(from = "network.host")]
+    host: String,
+    port: Option<u16>,
+    status: Status,
+    contact: Contact,
+}
+
+fn server() -> proto::Server {
+    proto::Server {
+        name: "api".to_owned(),
+        network: Some(proto::Network {
+            host: "localhost".to_owned(),
+        }),
+        port: Some(8080),
+        status: proto::Status::Down as i32,
+        contact: Some(proto::Contact::Email("ops@example.com".to_owned())),
+    }
+}
+
+#[test]
+fn reexported_crate() {
+    let native: Server = server().try_into_native().unwrap();
+
+    assert_eq!(
+        native,
+        Server {
+            name: "api".to_owned(),
+            host: "localhost".to_owned(),
+            port: Some(8080),
+            status: Status::Down,
+            contact: Contact::Email("ops@example.com".to_owned()),
+        }
+    );
+    let proto: proto::Server = native.into_proto();
+    assert_eq!(proto, server());
+}
+
+#[test]
+fn reexported_crate_errors() {
+    let proto = proto::Server {
+        name: String::new(),
+        ..server()
+    };
+    let native: Result<Server, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::InvalidField { .. })
+    ));
+}
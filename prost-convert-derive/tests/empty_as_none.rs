@@ -0,0 +1,152 @@
+// proto3 can't tell a field set to its zero value apart from an unset one.
+
+use prost_convert::{IntoProto, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Default)]
+    pub struct User {
+        pub name: String,
+        pub nickname: String,
+        pub age: u32,
+        pub groups: Vec<String>,
+        pub manager: Option<Manager>,
+    }
+
+    #[derive(PartialEq, Debug, Default)]
+    pub struct Manager {
+        pub name: String,
+    }
+
+    #[derive(PartialEq, Debug, Default)]
+    pub struct Profile {
+        pub name: String,
+        pub nickname: String,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Os {
+        Unspecified,
+        Linux,
+    }
+
+    #[derive(PartialEq, Debug, Default)]
+    pub struct Host {
+        pub os: i32,
+    }
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::User", empty_as_none)]
+pub struct User {
+    name: String,
+    nickname: Option<String>,
+    age: Option<u8>,
+    groups: Option<Vec<String>>,
+    manager: Option<Manager>,
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::Manager")]
+pub struct Manager {
+    name: String,
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::Profile")]
+pub struct Profile {
+    name: String,
+    #[prost_convert(empty_as_none)]
+    nickname: Option<String>,
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone, Copy)]
+#[prost_convert(src = "proto::Os")]
+pub enum Os {
+    Unspecified,
+    Linux,
+}
+
+#[derive(ProstConvert, PartialEq, Debug, Clone)]
+#[prost_convert(src = "proto::Host")]
+pub struct Host {
+    #[prost_convert(empty_as_none)]
+    os: Option<Os>,
+}
+
+#[test]
+fn zero_values_become_none() {
+    let proto = proto::User {
+        name: String::from("foo"),
+        ..Default::default()
+    };
+    let native: User = proto.try_into_native().unwrap();
+
+    assert_eq!(
+        native,
+        User {
+            name: String::from("foo"),
+            nickname: None,
+            age: None,
+            groups: None,
+            manager: None,
+        }
+    );
+}
+
+#[test]
+fn none_becomes_zero_value() {
+    let native = User {
+        name: String::from("foo"),
+        nickname: None,
+        age: None,
+        groups: None,
+        manager: None,
+    };
+    let proto: proto::User = native.into_proto();
+
+    assert_eq!(
+        proto,
+        proto::User {
+            name: String::from("foo"),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn set_values_are_kept() {
+    let native = User {
+        name: String::from("foo"),
+        nickname: Some(String::from("bar")),
+        age: Some(42),
+        groups: Some(vec![String::from("admin")]),
+        manager: Some(Manager {
+            name: String::from("baz"),
+        }),
+    };
+    let proto: proto::User = native.clone().into_proto();
+
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn field_attribute() {
+    let proto = proto::Profile::default();
+    let native: Profile = proto.try_into_native().unwrap();
+
+    assert_eq!(native.name, String::new());
+    assert_eq!(native.nickname, None);
+}
+
+#[test]
+fn enum_zero_value() {
+    let native: Host = proto::Host::default().try_into_native().unwrap();
+    assert_eq!(native.os, None);
+
+    let native = Host {
+        os: Some(Os::Linux),
+    };
+    let proto: proto::Host = native.clone().into_proto();
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
@@ -0,0 +1,157 @@
+use prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct NetworkConfig {
+        pub host: String,
+        pub port: u32,
+        pub tls: Option<Tls>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Tls {
+        pub certificate: String,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Config {
+        pub name: String,
+        pub network: Option<NetworkConfig>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Server {
+        pub name: String,
+        pub host: String,
+        pub port: u32,
+    }
+}
+
+// The native struct is flat while the proto one is nested.
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Config")]
+struct Config {
+    name: String,
+    #[prost_convert(from = "network.host")]
+    host: String,
+    #[prost_convert(from = "network.port")]
+    port: u32,
+    #[prost_convert(from = "network.tls.certificate")]
+    certificate: String,
+}
+
+// The native struct is nested while the proto one is flat.
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Server")]
+struct Server {
+    #[prost_convert(from = "name")]
+    server_name: String,
+    #[prost_convert(flatten)]
+    address: Address,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Server", proto_default)]
+struct Address {
+    host: String,
+    port: u32,
+}
+
+#[test]
+fn from_nested_path() {
+    let native = Config {
+        name: String::from("primary"),
+        host: String::from("localhost"),
+        port: 8080,
+        certificate: String::from("cert.pem"),
+    };
+    let proto: proto::Config = native.clone().into_proto();
+
+    assert_eq!(
+        proto,
+        proto::Config {
+            name: String::from("primary"),
+            network: Some(proto::NetworkConfig {
+                host: String::from("localhost"),
+                port: 8080,
+                tls: Some(proto::Tls {
+                    certificate: String::from("cert.pem"),
+                }),
+            }),
+        }
+    );
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn missing_nested_message_is_default() {
+    let proto = proto::Config {
+        name: String::from("primary"),
+        network: None,
+    };
+    let native: Config = proto.try_into_native().unwrap();
+
+    assert_eq!(
+        native,
+        Config {
+            name: String::from("primary"),
+            host: String::new(),
+            port: 0,
+            certificate: String::new(),
+        }
+    );
+}
+
+#[test]
+fn flatten() {
+    let native = Server {
+        server_name: String::from("primary"),
+        address: Address {
+            host: String::from("localhost"),
+            port: 8080,
+        },
+    };
+    let proto: proto::Server = native.clone().into_proto();
+
+    assert_eq!(
+        proto,
+        proto::Server {
+            name: String::from("primary"),
+            host: String::from("localhost"),
+            port: 8080,
+        }
+    );
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Server")]
+struct ValidatedServer {
+    name: String,
+    #[prost_convert(flatten)]
+    address: ValidatedAddress,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Server", proto_default)]
+struct ValidatedAddress {
+    #[prost_convert(validate(min_len = 1))]
+    host: String,
+    port: u32,
+}
+
+#[test]
+fn flattened_errors_are_reported() {
+    let proto = proto::Server {
+        name: String::from("primary"),
+        host: String::new(),
+        port: 8080,
+    };
+    let err = TryIntoNative::<ValidatedServer>::try_into_native(proto).unwrap_err();
+
+    assert!(matches!(
+        err,
+        ProstConvertError::InvalidField { field: "host", .. }
+    ));
+}
@@ -0,0 +1,81 @@
+use prost_convert::{IntoProto, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+// The names of the proto fields must not collide with the locals of the generated code.
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Version {
+        pub proto_version: u32,
+        pub version: u32,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Inner {
+        pub c: u32,
+        pub b_c: u32,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Outer {
+        pub b: Option<Inner>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Paths {
+        pub a_b: Option<Inner>,
+        pub a: Option<Outer>,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Version")]
+struct Version {
+    proto_version: u32,
+    version: u32,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Paths")]
+struct Paths {
+    #[prost_convert(from = "a_b.c")]
+    first: u32,
+    #[prost_convert(from = "a.b.b_c")]
+    second: u32,
+}
+
+#[test]
+fn prefixed_field_before_field() {
+    let proto = proto::Version {
+        proto_version: 1,
+        version: 2,
+    };
+    let native: Version = proto.clone().try_into_native().unwrap();
+
+    assert_eq!(
+        native,
+        Version {
+            proto_version: 1,
+            version: 2
+        }
+    );
+    assert_eq!(proto, native.into_proto());
+}
+
+#[test]
+fn paths_joined_by_underscores() {
+    let proto = proto::Paths {
+        a_b: Some(proto::Inner { c: 1, b_c: 0 }),
+        a: Some(proto::Outer {
+            b: Some(proto::Inner { c: 0, b_c: 2 }),
+        }),
+    };
+    let native: Paths = proto.try_into_native().unwrap();
+
+    assert_eq!(
+        native,
+        Paths {
+            first: 1,
+            second: 2
+        }
+    );
+}
@@ -2,23 +2,31 @@ use prost_convert::{IntoProto, TryIntoNative};
 use prost_convert_derive::ProstConvert;
 
 pub mod proto {
-    #[derive(PartialEq)]
+    #[derive(PartialEq, Debug)]
     pub enum TunnelType {
         ImplantTunnel(()),
         Forward(i32),
+        Close(Close),
     }
 
     #[derive(PartialEq)]
     pub enum ForwardTunnelTask {
         Socks5Proxy = 0,
     }
+
+    // An empty message, like `google.protobuf.Empty`.
+    #[derive(PartialEq, Debug, Default)]
+    pub struct Close {}
+
+    impl prost_convert::EmptyMessage for Close {}
 }
 
 #[derive(PartialEq, Debug, Clone, ProstConvert)]
 #[prost_convert(src = "proto::TunnelType")]
 enum TunnelType {
-    ImplantTunnel(()), // Sad workaround, ideally we shouldn't define newtype.
+    ImplantTunnel,
     Forward(ForwardTunnelTask),
+    Close,
 }
 
 #[derive(PartialEq, Debug, Clone, ProstConvert)]
@@ -34,3 +42,14 @@ fn it_works() {
 
     assert_eq!(native, proto.try_into_native().unwrap());
 }
+
+#[test]
+fn unit_variants_hold_empty_messages() {
+    let proto: proto::TunnelType = TunnelType::ImplantTunnel.into_proto();
+    assert_eq!(proto, proto::TunnelType::ImplantTunnel(()));
+    assert_eq!(TunnelType::ImplantTunnel, proto.try_into_native().unwrap());
+
+    let proto: proto::TunnelType = TunnelType::Close.into_proto();
+    assert_eq!(proto, proto::TunnelType::Close(proto::Close {}));
+    assert_eq!(TunnelType::Close, proto.try_into_native().unwrap());
+}
@@ -41,3 +41,44 @@ fn it_works() {
     let proto: proto::Computer = native.clone().into_proto();
     assert_eq!(native, proto.try_into_native().unwrap());
 }
+
+pub mod proto_level {
+    // Prost keeps the discriminants of the proto enum.
+    #[derive(PartialEq, Debug)]
+    pub enum Level {
+        Unspecified = 0,
+        Low = 5,
+        High = 10,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto_level::Level", allow_unmapped)]
+pub enum Level {
+    Low,
+    High,
+}
+
+#[test]
+fn discriminant_is_kept() {
+    let proto: i32 = Level::High.into_proto();
+    assert_eq!(proto, 10);
+
+    let native: Level = 5.try_into_native().unwrap();
+    assert_eq!(native, Level::Low);
+}
+
+#[test]
+fn unmapped_variant() {
+    let native: Result<Level, _> = 0.try_into_native();
+    assert!(matches!(
+        native,
+        Err(prost_convert::ProstConvertError::UnknownEnumValue(0))
+    ));
+
+    let native: Result<Level, _> = proto_level::Level::Unspecified.try_into_native();
+    assert!(matches!(
+        native,
+        Err(prost_convert::ProstConvertError::UnknownEnumValue(0))
+    ));
+}
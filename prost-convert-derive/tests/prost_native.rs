@@ -0,0 +1,48 @@
+use prost_convert::{ProstConvertError, ProstNative, ProstProto};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct User {
+        pub name: String,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::User", canonical)]
+pub struct User {
+    name: String,
+}
+
+// Another native type of the same proto type, which is not canonical.
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::User")]
+struct UserName {
+    name: String,
+}
+
+/// Generic code only needs the native type.
+fn roundtrip<N: ProstNative>(native: N) -> Result<N, ProstConvertError> {
+    N::from_proto_message(native.to_proto_message())
+}
+
+#[test]
+fn no_annotation() {
+    let user = User {
+        name: String::from("foo"),
+    };
+    let proto = user.clone().to_proto_message();
+
+    assert_eq!(proto.name, "foo");
+    assert_eq!(user, User::from_proto_message(proto.clone()).unwrap());
+    assert_eq!(user, proto.to_native_message().unwrap());
+}
+
+#[test]
+fn generic_helper() {
+    let name = UserName {
+        name: String::from("foo"),
+    };
+
+    assert_eq!(name, roundtrip(name.clone()).unwrap());
+}
@@ -0,0 +1,82 @@
+use prost_convert::{IntoProto, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    // Prost messages implement `Default`.
+    #[derive(PartialEq, Debug, Default)]
+    pub struct Request {
+        pub api_version: u32,
+        pub query: String,
+        pub deprecated_limit: u32,
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Response {
+        pub api_version: u32,
+        pub result: String,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Request", proto_default, set(api_version = "2"))]
+struct Request {
+    query: String,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(
+    src = "proto::Response",
+    allow_unmapped,
+    set(api_version = "API_VERSION")
+)]
+struct Response {
+    result: String,
+}
+
+const API_VERSION: u32 = 2;
+
+#[test]
+fn unmapped_fields_are_default() {
+    let native = Request {
+        query: String::from("foo"),
+    };
+    let proto: proto::Request = native.clone().into_proto();
+
+    assert_eq!(
+        proto,
+        proto::Request {
+            api_version: 2,
+            query: String::from("foo"),
+            deprecated_limit: 0,
+        }
+    );
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn unmapped_fields_are_ignored() {
+    let proto = proto::Request {
+        api_version: 1,
+        query: String::from("foo"),
+        deprecated_limit: 10,
+    };
+    let native: Request = proto.try_into_native().unwrap();
+
+    assert_eq!(
+        native,
+        Request {
+            query: String::from("foo")
+        }
+    );
+}
+
+#[test]
+fn set_without_default() {
+    let native = Response {
+        result: String::from("bar"),
+    };
+    let proto: proto::Response = native.clone().into_proto();
+
+    assert_eq!(proto.api_version, API_VERSION);
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
@@ -0,0 +1,127 @@
+use prost_convert::{IntoProto, ProstConvertError, TryFromProto, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    // Prost box recursive messages.
+    #[derive(PartialEq)]
+    pub struct Node {
+        pub id: u32,
+        pub next: Option<Box<Node>>,
+    }
+
+    #[derive(PartialEq)]
+    pub struct Tree {
+        pub children: Vec<Tree>,
+    }
+
+    pub enum Expr {
+        Leaf(u32),
+        Neg(Box<Expr>),
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Node")]
+struct Node {
+    id: u32,
+    next: Option<Box<Node>>,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Tree")]
+struct Tree {
+    children: Vec<Tree>,
+}
+
+enum Expr {
+    Leaf(u32),
+    Neg(Box<Expr>),
+}
+
+// Handwritten without a `DepthGuard`, the depth is only tracked by the conversion of the box.
+impl TryFromProto<proto::Expr> for Expr {
+    fn try_from_proto(value: proto::Expr) -> Result<Self, ProstConvertError> {
+        Ok(match value {
+            proto::Expr::Leaf(value) => Self::Leaf(value),
+            proto::Expr::Neg(expr) => Self::Neg(expr.try_into_native()?),
+        })
+    }
+}
+
+fn linked_list(len: u32) -> proto::Node {
+    let mut node = proto::Node { id: 0, next: None };
+    for id in 1..len {
+        node = proto::Node {
+            id,
+            next: Some(Box::new(node)),
+        };
+    }
+    node
+}
+
+#[test]
+fn recursive_message() {
+    let native = Node {
+        id: 1,
+        next: Some(Box::new(Node { id: 2, next: None })),
+    };
+    let proto: proto::Node = native.clone().into_proto();
+
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn deeply_nested_message_is_rejected() {
+    let proto = linked_list(10_000);
+    let native: Result<Node, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::RecursionLimitExceeded(_)
+    ));
+}
+
+#[test]
+fn deeply_nested_vector_is_rejected() {
+    let mut proto = proto::Tree { children: vec![] };
+    for _ in 0..10_000 {
+        proto = proto::Tree {
+            children: vec![proto],
+        };
+    }
+    let native: Result<Tree, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::RecursionLimitExceeded(_)
+    ));
+}
+
+#[test]
+fn deeply_nested_box_is_rejected() {
+    let native: Result<Expr, _> =
+        proto::Expr::Neg(Box::new(proto::Expr::Leaf(1))).try_into_native();
+    assert!(matches!(native, Ok(Expr::Neg(expr)) if matches!(*expr, Expr::Leaf(1))));
+
+    let mut proto = proto::Expr::Leaf(0);
+    for _ in 0..10_000 {
+        proto = proto::Expr::Neg(Box::new(proto));
+    }
+    let native: Result<Expr, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::RecursionLimitExceeded(_)
+    ));
+}
+
+#[test]
+fn depth_is_restored_after_conversion() {
+    // Each conversion starts back from depth zero, whether the previous one succeeded or failed.
+    for len in [50, 10_000, 50] {
+        let _: Result<Node, _> = linked_list(len).try_into_native();
+    }
+    let native: Result<Node, _> = linked_list(50).try_into_native();
+
+    assert!(native.is_ok());
+}
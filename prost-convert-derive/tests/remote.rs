@@ -0,0 +1,147 @@
+use std::ops::Range;
+
+use prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+// A crate whose types can't derive `ProstConvert`.
+mod vendor {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Level {
+        Low,
+        High,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub enum Target {
+        Host(String),
+        Port(u16),
+    }
+}
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Range {
+        pub start: u32,
+        pub end: u32,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    #[repr(i32)]
+    pub enum Level {
+        Low = 0,
+        High = 1,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub enum Target {
+        Host(String),
+        Port(u32),
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct Job {
+        pub name: String,
+        pub lines: Option<Range>,
+        pub columns: Option<Range>,
+        pub level: i32,
+        pub target: Option<Target>,
+    }
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Range", remote = "std::ops::Range<u32>")]
+struct RangeDef {
+    start: u32,
+    end: u32,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Level", remote = "vendor::Level")]
+enum LevelDef {
+    Low,
+    High,
+}
+
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Target", remote = "vendor::Target")]
+enum TargetDef {
+    Host(String),
+    Port(u16),
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Job")]
+struct Job {
+    name: String,
+    #[prost_convert(with = "RangeDef")]
+    lines: Range<u32>,
+    #[prost_convert(with = "RangeDef")]
+    columns: Option<Range<u32>>,
+    #[prost_convert(with = "LevelDef")]
+    level: vendor::Level,
+    #[prost_convert(with = "TargetDef")]
+    target: vendor::Target,
+}
+
+fn job() -> proto::Job {
+    proto::Job {
+        name: "build".to_owned(),
+        lines: Some(proto::Range { start: 1, end: 10 }),
+        columns: None,
+        level: proto::Level::High as i32,
+        target: Some(proto::Target::Port(8080)),
+    }
+}
+
+#[test]
+fn remote_functions() {
+    assert_eq!(
+        RangeDef::from_native(3..7),
+        proto::Range { start: 3, end: 7 }
+    );
+    assert_eq!(
+        RangeDef::try_from_proto(proto::Range { start: 3, end: 7 }).unwrap(),
+        3..7
+    );
+    assert_eq!(LevelDef::from_native(vendor::Level::High), 1);
+    assert_eq!(LevelDef::try_from_proto(0).unwrap(), vendor::Level::Low);
+    assert!(matches!(
+        LevelDef::try_from_proto(2),
+        Err(ProstConvertError::UnknownEnumValue(2))
+    ));
+    assert_eq!(
+        TargetDef::from_native(vendor::Target::Host("localhost".to_owned())),
+        proto::Target::Host("localhost".to_owned())
+    );
+}
+
+#[test]
+fn remote_fields_roundtrip() {
+    let native: Job = job().try_into_native().unwrap();
+
+    assert_eq!(native.lines, 1..10);
+    assert_eq!(native.columns, None);
+    assert_eq!(native.level, vendor::Level::High);
+    assert_eq!(native.target, vendor::Target::Port(8080));
+    assert_eq!(job(), native.into_proto());
+}
+
+#[test]
+fn remote_field_errors() {
+    let proto = proto::Job {
+        lines: None,
+        ..job()
+    };
+    let native: Result<Job, _> = proto.try_into_native();
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::MissingRequiredField)
+    ));
+
+    let proto = proto::Job {
+        target: Some(proto::Target::Port(u32::MAX)),
+        ..job()
+    };
+    let native: Result<Job, _> = proto.try_into_native();
+    assert!(native.is_err());
+}
@@ -0,0 +1,65 @@
+use proptest_derive::Arbitrary;
+use prost_convert::testing::assert_roundtrip;
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub enum Level {
+        #[default]
+        Low,
+        High,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Alarm {
+        pub name: String,
+        pub threshold: u32,
+        pub level: Level,
+        pub history: Vec<i64>,
+        pub acknowledged: Option<bool>,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert, Arbitrary)]
+#[prost_convert(src = "proto::Level")]
+enum Level {
+    Low,
+    High,
+}
+
+// Emits a test checking the round trip of random alarms.
+#[derive(PartialEq, Debug, Clone, ProstConvert, Arbitrary)]
+#[prost_convert(src = "proto::Alarm", roundtrip_test)]
+struct Alarm {
+    name: String,
+    threshold: u32,
+    level: Level,
+    history: Vec<i64>,
+    acknowledged: Option<bool>,
+}
+
+#[test]
+fn roundtrip() {
+    assert_roundtrip::<proto::Alarm, _>(Alarm {
+        name: String::from("disk"),
+        threshold: 90,
+        level: Level::High,
+        history: vec![80, 85],
+        acknowledged: None,
+    });
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Alarm", proto_default)]
+struct AlarmName {
+    #[prost_convert(validate(min_len = 1))]
+    name: String,
+}
+
+#[test]
+#[should_panic(expected = "failed to convert")]
+fn roundtrip_failure() {
+    assert_roundtrip::<proto::Alarm, _>(AlarmName {
+        name: String::new(),
+    });
+}
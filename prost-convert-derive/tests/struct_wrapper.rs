@@ -0,0 +1,78 @@
+use prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct Config {
+        pub name: String,
+    }
+
+    // Generated for `message ConfigWrapper { Config payload = 1; }`.
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct ConfigWrapper {
+        pub payload: Option<Config>,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub enum HTTPMethod {
+        Get,
+        Post,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct HTTPMethodWrapper {
+        pub http_method: HTTPMethod,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(
+    src = "proto::Config",
+    wrapper = "proto::ConfigWrapper",
+    wrapper_field = "payload"
+)]
+struct Config {
+    name: String,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::HTTPMethod", wrapper = "proto::HTTPMethodWrapper")]
+enum HTTPMethod {
+    Get,
+    Post,
+}
+
+#[test]
+fn struct_wrapper_with_optional_field() {
+    let native = Config {
+        name: String::from("foo"),
+    };
+    let proto: proto::ConfigWrapper = native.clone().into_proto();
+
+    assert_eq!(
+        proto,
+        proto::ConfigWrapper {
+            payload: Some(proto::Config {
+                name: String::from("foo"),
+            }),
+        }
+    );
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn struct_wrapper_missing_field() {
+    let proto = proto::ConfigWrapper { payload: None };
+    let err = TryIntoNative::<Config>::try_into_native(proto).unwrap_err();
+
+    assert!(matches!(err, ProstConvertError::MissingRequiredField));
+}
+
+#[test]
+fn acronym_wrapper_field() {
+    let native = HTTPMethod::Post;
+    let proto: proto::HTTPMethodWrapper = native.clone().into_proto();
+
+    assert_eq!(proto.http_method, proto::HTTPMethod::Post);
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
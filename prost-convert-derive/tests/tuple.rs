@@ -0,0 +1,158 @@
+use prost_convert::{IntoProto, ProstConvertError, TryFromProto, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Point {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Move {
+        pub dx: i32,
+        pub dy: i32,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Teleport {
+        pub target: Option<Point>,
+        pub delay: Option<u32>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Wait {
+        pub seconds: u32,
+        pub reason: String,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct Say {
+        pub text: String,
+        pub volume: u32,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub enum Action {
+        Move(Move),
+        Teleport(Teleport),
+        Wait(Wait),
+        Jump(i32),
+        Say(Say),
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Point", fields("x", "y"))]
+struct Point(f64, f64);
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Action")]
+enum Action {
+    #[prost_convert(src = "proto::Move", fields("dx", "dy"))]
+    Move(i16, i16),
+    #[prost_convert(src = "proto::Teleport", fields("target", "delay"))]
+    Teleport(Point, Option<u16>),
+    // `reason` is not mapped.
+    #[prost_convert(src = "proto::Wait", fields("seconds"), proto_default)]
+    Wait(u8),
+    Jump(i32),
+    #[prost_convert(src = "proto::Say", fields("text", "volume"))]
+    Say(
+        #[prost_convert(validate(min_len = 1))] String,
+        #[prost_convert(validate(range = "0..=10"))] u32,
+    ),
+}
+
+#[test]
+fn tuple_struct() {
+    let proto = proto::Point { x: 1.5, y: -2.0 };
+    let native: Point = proto.clone().try_into_native().unwrap();
+
+    assert_eq!(native, Point(1.5, -2.0));
+    assert_eq!(proto, native.into_proto());
+}
+
+#[test]
+fn tuple_variants() {
+    let actions = [
+        (
+            proto::Action::Move(proto::Move { dx: 3, dy: -4 }),
+            Action::Move(3, -4),
+        ),
+        (
+            proto::Action::Teleport(proto::Teleport {
+                target: Some(proto::Point { x: 0.0, y: 1.0 }),
+                delay: None,
+            }),
+            Action::Teleport(Point(0.0, 1.0), None),
+        ),
+        (
+            proto::Action::Wait(proto::Wait {
+                seconds: 10,
+                reason: String::new(),
+            }),
+            Action::Wait(10),
+        ),
+        (proto::Action::Jump(2), Action::Jump(2)),
+        (
+            proto::Action::Say(proto::Say {
+                text: "hello".to_owned(),
+                volume: 3,
+            }),
+            Action::Say("hello".to_owned(), 3),
+        ),
+    ];
+    for (proto, native) in actions {
+        assert_eq!(native, proto.clone().try_into_native().unwrap());
+        let converted: proto::Action = native.into_proto();
+        assert_eq!(proto, converted);
+    }
+}
+
+#[test]
+fn tuple_variant_errors() {
+    let proto = proto::Action::Move(proto::Move {
+        dx: i32::MAX,
+        dy: 0,
+    });
+    let native: Result<Action, _> = proto.try_into_native();
+    assert!(native.is_err());
+
+    let proto = proto::Action::Teleport(proto::Teleport::default());
+    let native: Result<Action, _> = proto.try_into_native();
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::MissingRequiredField)
+    ));
+}
+
+#[test]
+fn tuple_variant_rules() {
+    let proto = proto::Action::Say(proto::Say {
+        text: String::new(),
+        volume: 3,
+    });
+    let native: Result<Action, _> = proto.try_into_native();
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::InvalidField { field: "text", .. })
+    ));
+}
+
+#[test]
+fn tuple_variant_errors_are_collected() {
+    let proto = proto::Action::Say(proto::Say {
+        text: String::new(),
+        volume: 11,
+    });
+    let errors = Action::try_from_proto_all(proto).unwrap_err();
+    assert_eq!(errors.len(), 2);
+
+    let proto = proto::Action::Move(proto::Move {
+        dx: i32::MAX,
+        dy: i32::MIN,
+    });
+    let errors = Action::try_from_proto_all(proto).unwrap_err();
+    assert_eq!(errors.len(), 2);
+}
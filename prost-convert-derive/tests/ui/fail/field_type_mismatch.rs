@@ -0,0 +1,23 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub struct Message {
+        pub name: String,
+        pub port: u32,
+        pub address: String,
+        pub backup: Option<String>,
+    }
+}
+
+pub struct Address;
+
+// The error is reported on the field whose type can't be converted.
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Message")]
+pub struct Message {
+    name: String,
+    port: u16,
+    address: Address,
+    backup: Option<Address>,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub struct Network {
+        pub host: String,
+    }
+
+    pub struct Config {
+        pub network: Option<Network>,
+    }
+}
+
+// A native field reading a nested message can't read one of its fields too.
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Config")]
+pub struct Config {
+    #[prost_convert(from = "network.host")]
+    host: String,
+    network: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub struct Range {
+        pub start: u32,
+        pub stop: u32,
+    }
+}
+
+// The fields of a mirror must be the ones of the foreign type.
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Range", remote = "std::ops::Range<u32>")]
+pub struct RangeDef {
+    start: u32,
+    stop: u32,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub struct Move {
+        pub dx: i32,
+        pub dy: i32,
+    }
+
+    pub enum Action {
+        Move(Move),
+    }
+}
+
+// The proto fields of a tuple variant can't be deduced from the positions of its fields.
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Action")]
+pub enum Action {
+    Move(i32, i32),
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub mod v1 {
+        pub struct User {
+            pub name: String,
+        }
+    }
+    pub mod v2 {
+        pub struct User {
+            pub name: String,
+        }
+    }
+}
+
+// The attributes of a proto version must refer to a version declared in `src(...)`.
+#[derive(ProstConvert)]
+#[prost_convert(src(v1 = "proto::v1::User", v2 = "proto::v2::User"))]
+pub struct User {
+    #[prost_convert(v3(skip))]
+    name: String,
+}
+
+fn main() {}
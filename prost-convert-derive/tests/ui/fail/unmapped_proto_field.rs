@@ -0,0 +1,16 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub struct Message {
+        pub name: String,
+        pub added: u32,
+    }
+}
+
+// A proto field which is not in the native struct must be reviewed.
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Message")]
+pub struct Message {
+    name: String,
+}
+
+fn main() {}
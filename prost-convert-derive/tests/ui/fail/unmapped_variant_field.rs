@@ -0,0 +1,21 @@
+use prost_convert_derive::ProstConvert;
+pub mod proto {
+    pub struct Move {
+        pub dx: i32,
+        pub dy: i32,
+    }
+
+    pub enum Action {
+        Move(Move),
+    }
+}
+
+// A field of the message held by a tuple variant must be mapped, like the one of a struct.
+#[derive(ProstConvert)]
+#[prost_convert(src = "proto::Action")]
+pub enum Action {
+    #[prost_convert(src = "proto::Move", fields("dx"))]
+    Move(i32),
+}
+
+fn main() {}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use prost_convert::{IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    #[derive(PartialEq)]
+    pub struct Interval {
+        pub start: u64,
+        pub end: u64,
+    }
+
+    #[derive(PartialEq)]
+    pub enum Endpoint {
+        Port(u32),
+        Socket(String),
+    }
+
+    #[derive(PartialEq, Clone, Copy)]
+    #[repr(i32)]
+    pub enum Level {
+        Debug = 0,
+        Info = 1,
+        Trace = 2,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Interval", validate = "Interval::validate")]
+pub struct Interval {
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    fn validate(&self) -> Result<(), InvalidInterval> {
+        if self.start > self.end {
+            return Err(InvalidInterval);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidInterval;
+
+impl fmt::Display for InvalidInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("interval start is after its end")
+    }
+}
+
+impl std::error::Error for InvalidInterval {}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Endpoint", validate = "validate_endpoint")]
+pub enum Endpoint {
+    Port(u16),
+    Socket(std::net::SocketAddr),
+}
+
+fn validate_endpoint(endpoint: &Endpoint) -> Result<(), InvalidPort> {
+    match endpoint {
+        Endpoint::Port(0) => Err(InvalidPort),
+        Endpoint::Socket(addr) if addr.port() == 0 => Err(InvalidPort),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidPort;
+
+impl fmt::Display for InvalidPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("port 0 is reserved")
+    }
+}
+
+impl std::error::Error for InvalidPort {}
+
+#[derive(PartialEq, Debug, Clone, Copy, ProstConvert)]
+#[prost_convert(src = "proto::Level", validate = "validate_level")]
+pub enum Level {
+    Debug,
+    Info,
+    Trace,
+}
+
+fn validate_level(level: &Level) -> Result<(), DeprecatedLevel> {
+    match level {
+        Level::Trace => Err(DeprecatedLevel),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug)]
+pub struct DeprecatedLevel;
+
+impl fmt::Display for DeprecatedLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the trace level is deprecated")
+    }
+}
+
+impl std::error::Error for DeprecatedLevel {}
+
+#[test]
+fn valid_values_are_converted() {
+    let native = Interval { start: 1, end: 2 };
+    let proto: proto::Interval = native.clone().into_proto();
+    assert_eq!(native, proto.try_into_native().unwrap());
+
+    let native = Endpoint::Port(80);
+    let proto: proto::Endpoint = native.clone().into_proto();
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn invalid_struct_is_rejected() {
+    let proto = proto::Interval { start: 2, end: 1 };
+    let native: Result<Interval, _> = proto.try_into_native();
+
+    let err = native.err().unwrap();
+    assert!(matches!(err, ProstConvertError::ValidationError(_)));
+    assert_eq!(
+        err.to_string(),
+        "validation failed: interval start is after its end"
+    );
+}
+
+#[test]
+fn invalid_enum_is_rejected() {
+    let proto = proto::Endpoint::Socket(String::from("127.0.0.1:0"));
+    let native: Result<Endpoint, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::ValidationError(_)
+    ));
+}
+
+#[test]
+fn invalid_enum_value_is_rejected() {
+    // Prost messages hold the enum as an `i32`.
+    let native: Result<Level, _> = (proto::Level::Info as i32).try_into_native();
+    assert_eq!(native.unwrap(), Level::Info);
+
+    let native: Result<Level, _> = (proto::Level::Trace as i32).try_into_native();
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::ValidationError(_)
+    ));
+
+    let native: Result<Level, _> = proto::Level::Trace.try_into_native();
+    assert!(matches!(
+        native.err().unwrap(),
+        ProstConvertError::ValidationError(_)
+    ));
+}
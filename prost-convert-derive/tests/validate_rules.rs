@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use prost_convert::{validate::Violation, IntoProto, ProstConvertError, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    use std::collections::HashMap;
+
+    #[derive(PartialEq, Default)]
+    pub struct Account {
+        pub login: String,
+        pub display_name: Option<String>,
+        pub age: u32,
+        pub score: f64,
+        pub tags: Vec<String>,
+        pub labels: HashMap<String, String>,
+    }
+
+    #[derive(PartialEq, Default)]
+    pub struct Network {
+        pub host: String,
+    }
+
+    #[derive(PartialEq, Default)]
+    pub struct Server {
+        pub network: Option<Network>,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Account")]
+pub struct Account {
+    #[prost_convert(validate(min_len = 1, max_len = 8, pattern = "^[a-z]+$"))]
+    login: String,
+    #[prost_convert(validate(max_len = 4))]
+    display_name: Option<String>,
+    #[prost_convert(validate(range = "1..=100"))]
+    age: u8,
+    #[prost_convert(validate(range = "0.0..1.0"))]
+    score: f64,
+    #[prost_convert(validate(max_items = 2))]
+    tags: Vec<String>,
+    #[prost_convert(validate(max_items = 1))]
+    labels: HashMap<String, String>,
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::Server")]
+pub struct Server {
+    #[prost_convert(from = "network.host", validate(min_len = 1))]
+    host: String,
+}
+
+fn valid() -> proto::Account {
+    proto::Account {
+        login: String::from("foo"),
+        display_name: Some(String::from("Foo")),
+        age: 42,
+        score: 0.5,
+        tags: vec![String::from("admin")],
+        labels: HashMap::new(),
+    }
+}
+
+fn violation(proto: proto::Account) -> (&'static str, Violation) {
+    let native: Result<Account, _> = proto.try_into_native();
+    match native.err().unwrap() {
+        ProstConvertError::InvalidField { field, violation } => (field, violation),
+        other => panic!("unexpected error {other:?}"),
+    }
+}
+
+#[test]
+fn valid_message() {
+    let native: Account = valid().try_into_native().unwrap();
+    let proto: proto::Account = native.clone().into_proto();
+
+    assert_eq!(native, proto.try_into_native().unwrap());
+}
+
+#[test]
+fn unset_optional_field_is_valid() {
+    let proto = proto::Account {
+        display_name: None,
+        ..valid()
+    };
+    let native: Account = proto.try_into_native().unwrap();
+
+    assert_eq!(native.display_name, None);
+}
+
+#[test]
+fn length() {
+    let proto = proto::Account {
+        login: String::new(),
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("login", Violation::MinLen { min: 1, actual: 0 })
+    );
+
+    let proto = proto::Account {
+        login: String::from("abcdefghi"),
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("login", Violation::MaxLen { max: 8, actual: 9 })
+    );
+
+    // Length is counted in characters.
+    let proto = proto::Account {
+        display_name: Some(String::from("éèàù")),
+        ..valid()
+    };
+    assert!(TryIntoNative::<Account>::try_into_native(proto).is_ok());
+
+    let proto = proto::Account {
+        display_name: Some(String::from("Foo Bar")),
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("display_name", Violation::MaxLen { max: 4, actual: 7 })
+    );
+}
+
+#[test]
+fn pattern() {
+    let proto = proto::Account {
+        login: String::from("Foo"),
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        (
+            "login",
+            Violation::Pattern {
+                pattern: "^[a-z]+$"
+            }
+        )
+    );
+}
+
+#[test]
+fn range() {
+    // Checked on the proto value, before it fails to fit into an `u8`.
+    let proto = proto::Account {
+        age: 1000,
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("age", Violation::Range { range: "1..=100" })
+    );
+
+    let proto = proto::Account {
+        score: 1.0,
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("score", Violation::Range { range: "0.0..1.0" })
+    );
+}
+
+#[test]
+fn max_items() {
+    let proto = proto::Account {
+        tags: vec![String::from("a"), String::from("b"), String::from("c")],
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("tags", Violation::MaxItems { max: 2, actual: 3 })
+    );
+
+    let proto = proto::Account {
+        labels: HashMap::from([
+            (String::from("a"), String::new()),
+            (String::from("b"), String::new()),
+        ]),
+        ..valid()
+    };
+    assert_eq!(
+        violation(proto),
+        ("labels", Violation::MaxItems { max: 1, actual: 2 })
+    );
+}
+
+#[test]
+fn error_message() {
+    let proto = proto::Account {
+        login: String::new(),
+        ..valid()
+    };
+    let native: Result<Account, _> = proto.try_into_native();
+
+    assert_eq!(
+        native.err().unwrap().to_string(),
+        "invalid field `login`: length 0 is lower than 1"
+    );
+}
+
+#[test]
+fn nested_field_is_named_by_its_proto_path() {
+    let proto = proto::Server {
+        network: Some(proto::Network {
+            host: String::new(),
+        }),
+    };
+    let native: Result<Server, _> = proto.try_into_native();
+
+    assert!(matches!(
+        native,
+        Err(ProstConvertError::InvalidField {
+            field: "network.host",
+            violation: Violation::MinLen { min: 1, actual: 0 },
+        })
+    ));
+}
@@ -0,0 +1,160 @@
+use prost_convert::{IntoProto, ProstNative, TryIntoNative};
+use prost_convert_derive::ProstConvert;
+
+pub mod proto {
+    pub mod v1 {
+        #[derive(PartialEq, Debug, Clone)]
+        pub struct User {
+            pub name: String,
+            pub email: String,
+            pub address: Option<Address>,
+            pub status: i32,
+        }
+
+        #[derive(PartialEq, Debug, Clone)]
+        pub struct Address {
+            pub city: String,
+        }
+
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        #[repr(i32)]
+        pub enum Status {
+            Active = 0,
+            Banned = 1,
+        }
+    }
+
+    pub mod v2 {
+        #[derive(PartialEq, Debug, Clone)]
+        pub struct User {
+            pub name: String,
+            pub email_address: String,
+            pub age: u32,
+            pub address: Option<Address>,
+            pub status: i32,
+        }
+
+        #[derive(PartialEq, Debug, Clone)]
+        pub struct Address {
+            pub city: String,
+        }
+
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        #[repr(i32)]
+        pub enum Status {
+            Active = 0,
+            Banned = 1,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src(v1 = "proto::v1::User", v2 = "proto::v2::User"), canonical)]
+pub struct User {
+    name: String,
+    #[prost_convert(v2(rename = "email_address"))]
+    email: String,
+    #[prost_convert(v1(skip))]
+    age: u32,
+    address: Address,
+    status: Status,
+}
+
+// Repeated `src` attributes, when no field differs between the versions.
+#[derive(PartialEq, Debug, Clone, ProstConvert)]
+#[prost_convert(src = "proto::v1::Address", src = "proto::v2::Address")]
+pub struct Address {
+    city: String,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, ProstConvert)]
+#[prost_convert(src = "proto::v1::Status", src = "proto::v2::Status")]
+pub enum Status {
+    Active,
+    Banned,
+}
+
+fn v1() -> proto::v1::User {
+    proto::v1::User {
+        name: "foo".to_owned(),
+        email: "foo@example.com".to_owned(),
+        address: Some(proto::v1::Address {
+            city: "Paris".to_owned(),
+        }),
+        status: proto::v1::Status::Banned as i32,
+    }
+}
+
+fn v2() -> proto::v2::User {
+    proto::v2::User {
+        name: "foo".to_owned(),
+        email_address: "foo@example.com".to_owned(),
+        age: 42,
+        address: Some(proto::v2::Address {
+            city: "Paris".to_owned(),
+        }),
+        status: proto::v2::Status::Banned as i32,
+    }
+}
+
+fn user(age: u32) -> User {
+    User {
+        name: "foo".to_owned(),
+        email: "foo@example.com".to_owned(),
+        age,
+        address: Address {
+            city: "Paris".to_owned(),
+        },
+        status: Status::Banned,
+    }
+}
+
+#[test]
+fn from_each_version() {
+    let from_v1: User = v1().try_into_native().unwrap();
+    let from_v2: User = v2().try_into_native().unwrap();
+
+    // The field missing from v1 is set to its default value.
+    assert_eq!(from_v1, user(0));
+    assert_eq!(from_v2, user(42));
+}
+
+#[test]
+fn into_each_version() {
+    let into_v1: proto::v1::User = user(42).into_proto();
+    let into_v2: proto::v2::User = user(42).into_proto();
+
+    assert_eq!(into_v1, v1());
+    assert_eq!(into_v2, v2());
+}
+
+#[test]
+fn migrate_between_versions() {
+    let from_v1: User = v1().try_into_native().unwrap();
+    let into_v2: proto::v2::User = from_v1.into_proto();
+
+    assert_eq!(into_v2, proto::v2::User { age: 0, ..v2() });
+}
+
+#[test]
+fn proto_type_is_the_first_version() {
+    let proto: proto::v1::User = user(42).to_proto_message();
+
+    assert_eq!(proto, v1());
+}
+
+#[test]
+fn repeated_src() {
+    let v1 = proto::v1::Status::Active;
+    let v2 = proto::v2::Status::Banned;
+
+    let from_v1: Status = v1.try_into_native().unwrap();
+    let from_v2: Status = v2.try_into_native().unwrap();
+
+    assert_eq!(from_v1, Status::Active);
+    assert_eq!(from_v2, Status::Banned);
+    assert_eq!(
+        IntoProto::<proto::v2::Status>::into_proto(Status::Active),
+        proto::v2::Status::Active
+    );
+}
@@ -0,0 +1,128 @@
+//! Encoding of native values into the protobuf wire format, and decoding from it.
+//!
+//! These helpers chain the conversion of this crate with the encoding of `prost::Message`, for
+//! the native values which are stored or sent as bytes.
+//!
+//! ```
+//! # use prost_convert::{FromNative, ProstConvertError, ProstNative, TryFromProto};
+//! # mod proto {
+//! #     #[derive(Clone, PartialEq, prost::Message)]
+//! #     pub struct User {
+//! #         #[prost(string, tag = "1")]
+//! #         pub name: String,
+//! #     }
+//! # }
+//! # #[derive(Clone, Debug, PartialEq)]
+//! # struct User {
+//! #     name: String,
+//! # }
+//! # impl TryFromProto<proto::User> for User {
+//! #     fn try_from_proto(value: proto::User) -> Result<Self, ProstConvertError> {
+//! #         Ok(Self { name: value.name })
+//! #     }
+//! # }
+//! # impl FromNative<User> for proto::User {
+//! #     fn from_native(value: User) -> Self {
+//! #         Self { name: value.name }
+//! #     }
+//! # }
+//! # impl ProstNative for User {
+//! #     type Proto = proto::User;
+//! # }
+//! let user = User { name: String::from("foo") };
+//! let bytes = prost_convert::codec::encode_native_to_vec::<proto::User, _>(&user);
+//! let decoded: User = prost_convert::codec::decode_native::<proto::User, _>(bytes.as_slice())?;
+//! assert_eq!(decoded, user);
+//!
+//! // The proto type of a `ProstNative` type is known.
+//! let decoded = prost_convert::codec::decode::<User>(bytes.as_slice())?;
+//! assert_eq!(decoded, user);
+//! # Ok::<(), prost_convert::codec::CodecError>(())
+//! ```
+
+use prost::bytes::{Buf, BufMut};
+use prost::Message;
+
+use crate::{IntoProto, ProstConvertError, ProstNative, TryFromProto, TryIntoNative};
+
+/// An error while encoding or decoding a native value.
+#[derive(thiserror::Error, Debug)]
+pub enum CodecError {
+    /// The buffer is too small for the encoded proto message.
+    #[error("failed to encode proto message: {0}")]
+    Encode(#[from] prost::EncodeError),
+    /// The bytes are not a valid proto message.
+    #[error("failed to decode proto message: {0}")]
+    Decode(#[from] prost::DecodeError),
+    /// The decoded proto message can't be converted into the native type.
+    #[error("failed to convert proto message: {0}")]
+    Convert(#[from] ProstConvertError),
+}
+
+/// Encode a native value as the proto message `P`.
+///
+/// The native value is cloned, since the conversion consumes it.
+pub fn encode_native<P, N>(native: &N, buf: &mut impl BufMut) -> Result<(), CodecError>
+where
+    N: IntoProto<P> + Clone,
+    P: Message,
+{
+    Ok(native.clone().into_proto().encode(buf)?)
+}
+
+/// Encode a native value as the proto message `P`, prefixed by its length.
+pub fn encode_native_length_delimited<P, N>(
+    native: &N,
+    buf: &mut impl BufMut,
+) -> Result<(), CodecError>
+where
+    N: IntoProto<P> + Clone,
+    P: Message,
+{
+    Ok(native.clone().into_proto().encode_length_delimited(buf)?)
+}
+
+/// Encode a native value as the proto message `P` in a new vector.
+pub fn encode_native_to_vec<P, N>(native: &N) -> Vec<u8>
+where
+    N: IntoProto<P> + Clone,
+    P: Message,
+{
+    native.clone().into_proto().encode_to_vec()
+}
+
+/// Decode the proto message `P` and convert it into a native value.
+pub fn decode_native<P, N>(buf: impl Buf) -> Result<N, CodecError>
+where
+    P: Message + Default,
+    N: TryFromProto<P>,
+{
+    Ok(P::decode(buf)?.try_into_native()?)
+}
+
+/// Decode the proto message `P` prefixed by its length, and convert it into a native value.
+pub fn decode_native_length_delimited<P, N>(buf: impl Buf) -> Result<N, CodecError>
+where
+    P: Message + Default,
+    N: TryFromProto<P>,
+{
+    Ok(P::decode_length_delimited(buf)?.try_into_native()?)
+}
+
+/// Encode a native value as its proto message.
+pub fn encode<N>(native: &N, buf: &mut impl BufMut) -> Result<(), CodecError>
+where
+    N: ProstNative + Clone,
+    N::Proto: Message,
+{
+    encode_native::<N::Proto, N>(native, buf)
+}
+
+/// Decode the proto message of a native type and convert it.
+pub fn decode<N>(buf: impl Buf) -> Result<N, CodecError>
+where
+    N: ProstNative,
+    N::Proto: Message + Default,
+{
+    decode_native::<N::Proto, N>(buf)
+}
@@ -14,9 +14,27 @@
 // FIXME: upgrade syn to 2.0
 #![allow(clippy::multiple_crate_versions)]
 
+#[cfg(feature = "prost")]
+pub mod codec;
+#[cfg(feature = "futures")]
+pub mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tonic")]
+pub mod tonic;
+pub mod validate;
+
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::num::TryFromIntError;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "indexmap")]
+use indexmap::{IndexMap, IndexSet};
 use std::{collections::HashMap, net::IpAddr};
 
 /// Used to do value-to-value conversions while consuming the input value. It is the reciprocal of
@@ -33,6 +51,11 @@ use std::{collections::HashMap, net::IpAddr};
 /// - `FromNative<T> for U` implies [`IntoProto`]`<U> for T`
 ///
 /// You should probabily use the derive macro to impl `FromNative<P>`
+#[diagnostic::on_unimplemented(
+    message = "the native type `{N}` can't be converted into the proto type `{Self}`",
+    label = "`FromNative<{N}>` is not implemented for `{Self}`",
+    note = "derive `ProstConvert` on `{N}`, or implement `FromNative<{N}>` for `{Self}`"
+)]
 pub trait FromNative<N>: Sized {
     /// Performs the conversion.
     fn from_native(value: N) -> Self;
@@ -70,9 +93,237 @@ pub trait IntoProto<P>: Sized {
 /// - `TryFromProto<T> for U` implies [`TryIntoNative`]`<U> for T`
 ///
 /// You should probabily use the derive macro to impl `TryFromProto<P>`
+#[diagnostic::on_unimplemented(
+    message = "the proto type `{P}` can't be converted into the native type `{Self}`",
+    label = "`TryFromProto<{P}>` is not implemented for `{Self}`",
+    note = "derive `ProstConvert` on `{Self}`, or implement `TryFromProto<{P}>` for `{Self}`"
+)]
 pub trait TryFromProto<P>: Sized {
     /// Performs the conversion.
     fn try_from_proto(value: P) -> Result<Self, ProstConvertError>;
+
+    /// Performs the conversion, reporting every error instead of stopping at the first one.
+    ///
+    /// Derived impls and the collection impls of this crate keep converting the remaining
+    /// fields and elements once one failed, see [`ErrorCollector`]. Handwritten impls which
+    /// don't use it still stop at their first error.
+    fn try_from_proto_all(value: P) -> Result<Self, Vec<ProstConvertError>> {
+        let _mode = CollectMode::enter();
+        Self::try_from_proto(value).map_err(ProstConvertError::into_errors)
+    }
+}
+
+/// A native type and the proto type it is converted from, named by the `src` attribute of the
+/// derive macro.
+///
+/// Since the proto type is an associated type, conversions through this trait need no type
+/// annotation, and generic code can be written over native types only.
+pub trait ProstNative: IntoProto<Self::Proto> + TryFromProto<Self::Proto> {
+    /// The proto type of this native type.
+    type Proto;
+
+    /// Convert into the proto type.
+    fn to_proto_message(self) -> Self::Proto {
+        self.into_proto()
+    }
+
+    /// Convert from the proto type.
+    fn from_proto_message(proto: Self::Proto) -> Result<Self, ProstConvertError> {
+        Self::try_from_proto(proto)
+    }
+}
+
+/// A proto type and its native type. The reciprocal of [`ProstNative`].
+///
+/// A proto type can be converted into several native types, so the derive macro only implements
+/// it for the native type annotated with `#[prost_convert(canonical)]`.
+pub trait ProstProto: Sized {
+    /// The native type of this proto type.
+    type Native: ProstNative<Proto = Self>;
+
+    /// Convert into the native type.
+    fn to_native_message(self) -> Result<Self::Native, ProstConvertError> {
+        Self::Native::try_from_proto(self)
+    }
+}
+
+/// Conversion of a `repeated` proto field into a set, failing on duplicated elements.
+///
+/// [`TryFromProto`] silently deduplicates the elements of a set, this trait is used instead when
+/// a duplicated element must be rejected with [`ProstConvertError::DuplicateElement`].
+/// The derive macro uses it for fields annotated with `#[prost_convert(duplicates = "reject")]`.
+#[diagnostic::on_unimplemented(
+    message = "the proto type `{P}` can't be converted into the set `{Self}`",
+    label = "`TryFromProtoUnique<{P}>` is not implemented for `{Self}`",
+    note = "`duplicates = \"reject\"` only applies to sets converted from a `repeated` field"
+)]
+pub trait TryFromProtoUnique<P>: Sized {
+    /// Performs the conversion.
+    fn try_from_proto_unique(value: P) -> Result<Self, ProstConvertError>;
+}
+
+/// Proto3 values whose zero value can't be told apart from an unset field.
+///
+/// proto3 doesn't encode a scalar field set to its zero value (`0`, `""`, an empty repeated
+/// field, ...), so on the receiving side "set to zero" and "unset" are the same. This trait allows
+/// converting such a field into `None` on the native side. The derive macro uses it for optional
+/// fields annotated with `#[prost_convert(empty_as_none)]`.
+pub trait ZeroValue {
+    /// Return `true` if the value is the zero value of its proto type.
+    fn is_zero_value(&self) -> bool;
+}
+
+/// A proto field holding a message, which prost wraps in an `Option`.
+///
+/// The derive macro uses it to reach the fields of a nested message, for native fields annotated
+/// with `#[prost_convert(from = "message.field")]`. An unset message reads as its default value.
+pub trait MessageField {
+    /// The message held by the field.
+    type Message;
+
+    /// Return the message, or its default value if it is unset.
+    fn into_message(self) -> Self::Message;
+
+    /// Return a mutable reference to the message, setting it to its default value if it is unset.
+    fn message_mut(&mut self) -> &mut Self::Message;
+}
+
+impl<T> MessageField for Option<T>
+where
+    T: Default,
+{
+    type Message = T;
+
+    fn into_message(self) -> T {
+        self.unwrap_or_default()
+    }
+
+    fn message_mut(&mut self) -> &mut T {
+        self.get_or_insert_with(T::default)
+    }
+}
+
+/// A proto message without fields, held by a oneof variant which is mapped to a native unit
+/// variant.
+///
+/// It is implemented for `()`, which prost generates for `google.protobuf.Empty`. The other empty
+/// messages must opt in:
+///
+/// ```
+/// # mod proto {
+/// #     #[derive(Default)]
+/// #     pub struct Stop {}
+/// # }
+/// impl prost_convert::EmptyMessage for proto::Stop {}
+/// ```
+pub trait EmptyMessage: Default {}
+
+impl EmptyMessage for () {}
+
+/// A proto field converted by the functions given with `#[prost_convert(with = "...")]`.
+///
+/// The functions convert the proto type itself, while prost wraps a message field in an `Option`.
+/// This trait lets the derive macro use them on both kinds of fields. An unset message field is
+/// missing.
+pub trait WithField<P>: Sized {
+    /// Return the proto field holding `value`.
+    fn from_proto(value: P) -> Self;
+
+    /// Return the value held by the proto field.
+    fn try_into_proto(self) -> Result<P, ProstConvertError>;
+}
+
+impl<P> WithField<P> for P {
+    fn from_proto(value: P) -> Self {
+        value
+    }
+
+    fn try_into_proto(self) -> Result<P, ProstConvertError> {
+        Ok(self)
+    }
+}
+
+impl<P> WithField<P> for Option<P> {
+    fn from_proto(value: P) -> Self {
+        Some(value)
+    }
+
+    fn try_into_proto(self) -> Result<P, ProstConvertError> {
+        self.ok_or(ProstConvertError::MissingRequiredField)
+    }
+}
+
+/// Conversion of some of the fields of a proto message into a native struct.
+///
+/// The derive macro implements it for structs annotated with `#[prost_convert(proto_default)]`, so
+/// that they can be flattened into another native struct with `#[prost_convert(flatten)]`. The
+/// converted fields are taken out of the message.
+pub trait TryFromProtoFields<P>: Sized {
+    /// The fields of the message converted into `Self`.
+    const PROTO_FIELDS: ProtoFields;
+
+    /// Performs the conversion.
+    fn try_from_proto_fields(value: &mut P) -> Result<Self, ProstConvertError>;
+}
+
+/// Conversion of a native struct into some of the fields of a proto message. The opposite of
+/// [`TryFromProtoFields`].
+pub trait IntoProtoFields<P> {
+    /// The fields of the message converted from `self`.
+    const PROTO_FIELDS: ProtoFields;
+
+    /// Set the fields of `proto` converted from `self`.
+    fn into_proto_fields(self, proto: &mut P);
+}
+
+/// The proto fields converted by a [`TryFromProtoFields`] or [`IntoProtoFields`] implementation.
+///
+/// The derive macro checks that the proto fields which a struct leaves to its flattened fields
+/// are converted by them, when the manifest of `prost-convert-build` knows the fields of the
+/// message.
+#[derive(Debug, Clone, Copy)]
+pub struct ProtoFields {
+    /// The names of the fields converted by the struct itself.
+    pub names: &'static [&'static str],
+    /// The fields converted by the structs flattened into it.
+    pub flattened: &'static [ProtoFields],
+}
+
+impl ProtoFields {
+    /// Return whether the field `name` is converted.
+    pub const fn contains(&self, name: &str) -> bool {
+        let mut i = 0;
+        while i < self.names.len() {
+            if str_eq(self.names[i], name) {
+                return true;
+            }
+            i += 1;
+        }
+        let mut i = 0;
+        while i < self.flattened.len() {
+            if self.flattened[i].contains(name) {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+}
+
+// `str` comparison is not const.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
 }
 
 /// An attempted conversion that consumes `self`, which may or may not be
@@ -86,6 +337,15 @@ pub trait TryFromProto<P>: Sized {
 pub trait TryIntoNative<N>: Sized {
     /// Performs the conversion.
     fn try_into_native(self) -> Result<N, ProstConvertError>;
+
+    /// Performs the conversion, reporting every error instead of stopping at the first one.
+    ///
+    /// See [`TryFromProto::try_from_proto_all`].
+    fn try_into_native_all(self) -> Result<N, Vec<ProstConvertError>> {
+        let _mode = CollectMode::enter();
+        self.try_into_native()
+            .map_err(ProstConvertError::into_errors)
+    }
 }
 
 // FIXME:
@@ -109,6 +369,232 @@ pub enum ProstConvertError {
     TryFromIntError(#[from] TryFromIntError),
     #[error("try to parse a type and failed")]
     TypeParseError(#[from] anyhow::Error),
+    #[error("validation failed: {0}")]
+    ValidationError(anyhow::Error),
+    #[error("invalid field `{field}`: {violation}")]
+    InvalidField {
+        field: &'static str,
+        violation: validate::Violation,
+    },
+    #[error("repeated field contains a duplicated element")]
+    DuplicateElement,
+    #[error("unknown enum value {0}")]
+    UnknownEnumValue(i32),
+    #[error("proto message is nested deeper than the recursion limit ({0})")]
+    RecursionLimitExceeded(usize),
+    #[error("{}", display_errors(.0))]
+    Multiple(Vec<ProstConvertError>),
+}
+
+impl ProstConvertError {
+    /// Return the list of errors held by this error, flattening [`ProstConvertError::Multiple`].
+    pub fn into_errors(self) -> Vec<ProstConvertError> {
+        match self {
+            ProstConvertError::Multiple(errors) => errors
+                .into_iter()
+                .flat_map(ProstConvertError::into_errors)
+                .collect(),
+            other => vec![other],
+        }
+    }
+}
+
+fn display_errors(errors: &[ProstConvertError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// ERROR COLLECTION
+////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    static COLLECT_MODE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Enable the collection of errors on the current thread until it is dropped.
+struct CollectMode {
+    previous: bool,
+}
+
+impl CollectMode {
+    fn enter() -> Self {
+        Self {
+            previous: COLLECT_MODE.with(|mode| mode.replace(true)),
+        }
+    }
+}
+
+impl Drop for CollectMode {
+    fn drop(&mut self) {
+        COLLECT_MODE.with(|mode| mode.set(self.previous));
+    }
+}
+
+/// Gather the errors of the fields or elements of a proto value being converted.
+///
+/// By default, [`ErrorCollector::collect`] returns the first error so that it is propagated
+/// right away. When running inside [`TryFromProto::try_from_proto_all`], the error is recorded
+/// instead and the conversion goes on with the next field.
+///
+/// ```
+/// # use prost_convert::{ErrorCollector, ProstConvertError, TryFromProto, TryIntoNative};
+/// # use std::net::IpAddr;
+/// # mod proto { pub struct Route { pub source: String, pub destination: String } }
+/// struct Route {
+///     source: IpAddr,
+///     destination: IpAddr,
+/// }
+///
+/// impl TryFromProto<proto::Route> for Route {
+///     fn try_from_proto(value: proto::Route) -> Result<Self, ProstConvertError> {
+///         let mut errors = ErrorCollector::new();
+///         let source = errors.collect(value.source.try_into_native())?;
+///         let destination = errors.collect(value.destination.try_into_native())?;
+///         let (Some(source), Some(destination)) = (source, destination) else {
+///             return Err(errors.into_error());
+///         };
+///         Ok(Self { source, destination })
+///     }
+/// }
+///
+/// let proto = proto::Route { source: String::from("foo"), destination: String::from("bar") };
+/// let errors = Route::try_from_proto_all(proto).err().unwrap();
+/// assert_eq!(errors.len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct ErrorCollector {
+    errors: Vec<ProstConvertError>,
+}
+
+impl ErrorCollector {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the converted value, or handle the error.
+    ///
+    /// If errors are collected, the error is recorded and `Ok(None)` is returned. Otherwise
+    /// the error is returned.
+    pub fn collect<T>(
+        &mut self,
+        result: Result<T, ProstConvertError>,
+    ) -> Result<Option<T>, ProstConvertError> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if COLLECT_MODE.with(Cell::get) => {
+                self.errors.extend(err.into_errors());
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Return an error if any was recorded.
+    pub fn finish(self) -> Result<(), ProstConvertError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.into_error())
+        }
+    }
+
+    /// Return the recorded errors as a single error.
+    pub fn into_error(mut self) -> ProstConvertError {
+        if self.errors.len() == 1 {
+            if let Some(err) = self.errors.pop() {
+                return err;
+            }
+        }
+        ProstConvertError::Multiple(self.errors)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// RECURSION LIMIT
+////////////////////////////////////////////////////////////////////////////////
+
+/// Default maximum nesting depth of a proto value converted into a native one.
+///
+/// Every nested message, `Option` and `Vec` counts as one level, so a chain of
+/// `Option<Box<Message>>` uses two levels per message. This leaves room for the
+/// 100 nested messages that prost accepts when decoding.
+pub const DEFAULT_RECURSION_LIMIT: usize = 200;
+
+static RECURSION_LIMIT: AtomicUsize = AtomicUsize::new(DEFAULT_RECURSION_LIMIT);
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Set the maximum nesting depth allowed when converting a proto value into a native one.
+///
+/// A proto value coming from the network can be arbitrarily nested, which would overflow the
+/// stack in the recursive [`TryFromProto::try_from_proto`] calls. Going deeper than this limit makes
+/// the conversion fail with [`ProstConvertError::RecursionLimitExceeded`] instead.
+///
+/// The limit is global to the process and defaults to [`DEFAULT_RECURSION_LIMIT`].
+pub fn set_recursion_limit(limit: usize) {
+    RECURSION_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Return the current maximum nesting depth, see [`set_recursion_limit`].
+pub fn recursion_limit() -> usize {
+    RECURSION_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Track the nesting depth of the conversion running on the current thread.
+///
+/// A guard is created when entering a nested conversion and the depth is restored when it is
+/// dropped. It is used by the derive macro and by the collection impls of this crate, and
+/// should be used by handwritten [`TryFromProto`] impls of recursive types.
+///
+/// ```
+/// # use prost_convert::{DepthGuard, ProstConvertError, TryFromProto, TryIntoNative};
+/// # mod proto { pub struct Node { pub next: Option<Box<Node>> } }
+/// struct Node {
+///     next: Option<Box<Node>>,
+/// }
+///
+/// impl TryFromProto<proto::Node> for Node {
+///     fn try_from_proto(value: proto::Node) -> Result<Self, ProstConvertError> {
+///         let _depth = DepthGuard::enter()?;
+///         Ok(Self {
+///             next: value.next.map(|next| next.try_into_native()).transpose()?,
+///         })
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DepthGuard {
+    // The depth is tracked per thread, so the guard must not be sent to another one.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl DepthGuard {
+    /// Enter one more nesting level, failing if the recursion limit is reached.
+    pub fn enter() -> Result<Self, ProstConvertError> {
+        let limit = recursion_limit();
+        DEPTH.with(|depth| {
+            if depth.get() >= limit {
+                return Err(ProstConvertError::RecursionLimitExceeded(limit));
+            }
+            depth.set(depth.get() + 1);
+            Ok(Self {
+                _not_send: PhantomData,
+            })
+        })
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -142,6 +628,7 @@ where
     T: TryFromProto<U>,
 {
     fn try_from_proto(value: Option<U>) -> Result<Self, ProstConvertError> {
+        let _depth = DepthGuard::enter()?;
         match value {
             Some(value) => value.try_into_native(),
             None => Err(ProstConvertError::MissingRequiredField),
@@ -170,10 +657,13 @@ where
     T: TryFromProto<U>,
 {
     fn try_from_proto(value: Vec<U>) -> Result<Self, ProstConvertError> {
+        let _depth = DepthGuard::enter()?;
+        let mut errors = ErrorCollector::new();
         let mut native = Vec::with_capacity(value.len());
         for element in value {
-            native.push(element.try_into_native()?)
+            native.extend(errors.collect(element.try_into_native())?);
         }
+        errors.finish()?;
         Ok(native)
     }
 }
@@ -192,6 +682,122 @@ where
     }
 }
 
+// Sets and other collections are built from `repeated` fields.
+macro_rules! impl_collection {
+    ( $collection:ident $(, $bound:path)* ) => {
+        impl<T, U> TryFromProto<Vec<U>> for $collection<T>
+        where
+            T: TryFromProto<U> $(+ $bound)*,
+        {
+            fn try_from_proto(value: Vec<U>) -> Result<Self, ProstConvertError> {
+                let _depth = DepthGuard::enter()?;
+                let mut errors = ErrorCollector::new();
+                let mut native = $collection::new();
+                for element in value {
+                    native.extend(errors.collect(element.try_into_native())?);
+                }
+                errors.finish()?;
+                Ok(native)
+            }
+        }
+
+        impl<T, U> FromNative<$collection<U>> for Vec<T>
+        where
+            T: FromNative<U>,
+        {
+            fn from_native(value: $collection<U>) -> Self {
+                value.into_iter().map(IntoProto::into_proto).collect()
+            }
+        }
+    };
+}
+
+impl_collection!(VecDeque);
+impl_collection!(HashSet, Eq, Hash);
+impl_collection!(BTreeSet, Ord);
+
+macro_rules! impl_unique {
+    ( $set:ident $(, $bound:path)* ) => {
+        impl<T, U> TryFromProtoUnique<Vec<U>> for $set<T>
+        where
+            T: TryFromProto<U> $(+ $bound)*,
+        {
+            fn try_from_proto_unique(value: Vec<U>) -> Result<Self, ProstConvertError> {
+                let _depth = DepthGuard::enter()?;
+                let mut errors = ErrorCollector::new();
+                let mut native = $set::new();
+                for element in value {
+                    if let Some(element) = errors.collect(element.try_into_native())? {
+                        if !native.insert(element) {
+                            errors.collect::<()>(Err(ProstConvertError::DuplicateElement))?;
+                        }
+                    }
+                }
+                errors.finish()?;
+                Ok(native)
+            }
+        }
+    };
+}
+
+impl_unique!(HashSet, Eq, Hash);
+impl_unique!(BTreeSet, Ord);
+
+#[cfg(feature = "indexmap")]
+impl_collection!(IndexSet, Eq, Hash);
+
+#[cfg(feature = "indexmap")]
+impl_unique!(IndexSet, Eq, Hash);
+
+#[cfg(feature = "smallvec")]
+impl<A, U> TryFromProto<Vec<U>> for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: TryFromProto<U>,
+{
+    fn try_from_proto(value: Vec<U>) -> Result<Self, ProstConvertError> {
+        let _depth = DepthGuard::enter()?;
+        let mut errors = ErrorCollector::new();
+        let mut native = smallvec::SmallVec::with_capacity(value.len());
+        for element in value {
+            native.extend(errors.collect(element.try_into_native())?);
+        }
+        errors.finish()?;
+        Ok(native)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A, T> FromNative<smallvec::SmallVec<A>> for Vec<T>
+where
+    A: smallvec::Array,
+    T: FromNative<A::Item>,
+{
+    fn from_native(value: smallvec::SmallVec<A>) -> Self {
+        value.into_iter().map(IntoProto::into_proto).collect()
+    }
+}
+
+// Recursive messages are boxed by prost.
+impl<T, U> TryFromProto<Box<U>> for Box<T>
+where
+    T: TryFromProto<U>,
+{
+    fn try_from_proto(value: Box<U>) -> Result<Self, ProstConvertError> {
+        let _depth = DepthGuard::enter()?;
+        Ok(Box::new((*value).try_into_native()?))
+    }
+}
+
+impl<T, U> FromNative<Box<U>> for Box<T>
+where
+    T: FromNative<U>,
+{
+    fn from_native(value: Box<U>) -> Self {
+        Box::new((*value).into_proto())
+    }
+}
+
 /// We provide an implementation for all prost scalar value.
 /// <https://github.com/tokio-rs/prost#scalar-values>
 macro_rules! impl_scalar {
@@ -215,6 +821,46 @@ macro_rules! impl_scalar {
 
 impl_scalar!(f32, f64, i32, i64, u32, u64, bool, String, Vec<u8>);
 
+macro_rules! impl_zero_value {
+    ( $($t:ty),* ) => {
+        $(
+            impl ZeroValue for $t {
+                fn is_zero_value(&self) -> bool {
+                    *self == <$t>::default()
+                }
+            }
+        )*
+    };
+}
+
+impl_zero_value!(f32, f64, i32, i64, u32, u64, bool);
+
+impl ZeroValue for String {
+    fn is_zero_value(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+// Covers `bytes` and `repeated` fields.
+impl<T> ZeroValue for Vec<T> {
+    fn is_zero_value(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<K, V> ZeroValue for HashMap<K, V> {
+    fn is_zero_value(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+// Fields with explicit presence (messages, `optional` scalars) are only absent when unset.
+impl<T> ZeroValue for Option<T> {
+    fn is_zero_value(&self) -> bool {
+        self.is_none()
+    }
+}
+
 macro_rules! impl_map {
     ( $($t:ty),* ) => {
 
@@ -224,10 +870,15 @@ macro_rules! impl_map {
                 T: TryFromProto<U>,
             {
                 fn try_from_proto(value: HashMap<$t, U>) -> Result<Self, ProstConvertError> {
+                    let _depth = DepthGuard::enter()?;
+                    let mut errors = ErrorCollector::new();
                     let mut native = HashMap::with_capacity(value.len());
                     for (key, value) in value {
-                        native.insert(key, value.try_into_native()?);
+                        if let Some(value) = errors.collect(value.try_into_native())? {
+                            native.insert(key, value);
+                        }
                     }
+                    errors.finish()?;
                     Ok(native)
                 }
             }
@@ -244,6 +895,39 @@ macro_rules! impl_map {
                     proto
                 }
             }
+
+            #[cfg(feature = "indexmap")]
+            impl<T, U> TryFromProto<HashMap<$t, U>> for IndexMap<$t, T>
+            where
+                T: TryFromProto<U>,
+            {
+                fn try_from_proto(value: HashMap<$t, U>) -> Result<Self, ProstConvertError> {
+                    let _depth = DepthGuard::enter()?;
+                    let mut errors = ErrorCollector::new();
+                    let mut native = IndexMap::with_capacity(value.len());
+                    for (key, value) in value {
+                        if let Some(value) = errors.collect(value.try_into_native())? {
+                            native.insert(key, value);
+                        }
+                    }
+                    errors.finish()?;
+                    Ok(native)
+                }
+            }
+
+            #[cfg(feature = "indexmap")]
+            impl<T, U> FromNative<IndexMap<$t, U>> for HashMap<$t, T>
+            where
+                T: FromNative<U>,
+            {
+                fn from_native(value: IndexMap<$t, U>) -> Self {
+                    let mut proto = HashMap::with_capacity(value.len());
+                    for (key, value) in value {
+                        proto.insert(key, value.into_proto());
+                    }
+                    proto
+                }
+            }
         )*
     };
 }
@@ -388,6 +1072,56 @@ impl TryFromProto<String> for uuid::Uuid {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// WELL KNOWN TYPES
+////////////////////////////////////////////////////////////////////////////////
+
+// The wrapper types (`google.protobuf.StringValue`, ...) are already generated as `Option` of a
+// scalar by prost.
+
+#[cfg(feature = "prost-types")]
+impl_scalar!(
+    prost_types::Any,
+    prost_types::Struct,
+    prost_types::Value,
+    prost_types::ListValue,
+    prost_types::FieldMask
+);
+
+#[cfg(feature = "prost-types")]
+impl TryFromProto<prost_types::Timestamp> for std::time::SystemTime {
+    fn try_from_proto(value: prost_types::Timestamp) -> Result<Self, ProstConvertError> {
+        std::time::SystemTime::try_from(value)
+            .map_err(|err| ProstConvertError::TypeParseError(err.into()))
+    }
+}
+
+#[cfg(feature = "prost-types")]
+impl FromNative<std::time::SystemTime> for prost_types::Timestamp {
+    fn from_native(value: std::time::SystemTime) -> Self {
+        value.into()
+    }
+}
+
+#[cfg(feature = "prost-types")]
+impl TryFromProto<prost_types::Duration> for std::time::Duration {
+    fn try_from_proto(value: prost_types::Duration) -> Result<Self, ProstConvertError> {
+        std::time::Duration::try_from(value)
+            .map_err(|err| ProstConvertError::TypeParseError(err.into()))
+    }
+}
+
+#[cfg(feature = "prost-types")]
+impl FromNative<std::time::Duration> for prost_types::Duration {
+    /// Saturate to the greatest proto duration, which is lower than the greatest native one.
+    fn from_native(value: std::time::Duration) -> Self {
+        prost_types::Duration::try_from(value).unwrap_or(prost_types::Duration {
+            seconds: i64::MAX,
+            nanos: 999_999_999,
+        })
+    }
+}
+
 // Re-export #[derive(ProstConvert)].
 //
 // The reason re-exporting is not enabled by default is that disabling it would
@@ -0,0 +1,141 @@
+//! Conversion of the streams of proto messages received by streaming gRPC methods.
+//!
+//! ```
+//! # use prost_convert::{ProstConvertError, TryFromProto, TryIntoNative};
+//! # mod proto {
+//! #     pub struct Order {
+//! #         pub quantity: u32,
+//! #     }
+//! # }
+//! # struct Order {
+//! #     quantity: u16,
+//! # }
+//! # impl TryFromProto<proto::Order> for Order {
+//! #     fn try_from_proto(value: proto::Order) -> Result<Self, ProstConvertError> {
+//! #         Ok(Self { quantity: value.quantity.try_into_native()? })
+//! #     }
+//! # }
+//! use futures_core::Stream;
+//! use prost_convert::stream::{OnError, TryIntoNativeStreamExt};
+//!
+//! fn orders(
+//!     messages: impl Stream<Item = Result<proto::Order, ProstConvertError>>,
+//! ) -> impl Stream<Item = Result<Order, ProstConvertError>> {
+//!     messages.try_into_native_stream::<Order>()
+//! }
+//!
+//! fn valid_orders(
+//!     messages: impl Stream<Item = Result<proto::Order, ProstConvertError>>,
+//! ) -> impl Stream<Item = Result<Order, ProstConvertError>> {
+//!     messages.try_into_native_stream_with::<Order>(OnError::Skip)
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{ProstConvertError, TryFromProto, TryIntoNative};
+
+/// What a converted stream does with a message which can't be converted.
+///
+/// Errors of the stream of proto messages itself are always yielded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// Yield the conversion error, and keep converting the next messages.
+    #[default]
+    Yield,
+    /// Drop the message, and keep converting the next messages.
+    Skip,
+    /// Yield the conversion error, and end the stream.
+    Terminate,
+}
+
+/// Extension trait converting a stream of proto messages into a stream of native values.
+pub trait TryIntoNativeStreamExt<P, E>: Stream<Item = Result<P, E>> + Sized {
+    /// Convert every message of the stream, yielding the conversion errors.
+    fn try_into_native_stream<N>(self) -> TryIntoNativeStream<Self, N>
+    where
+        N: TryFromProto<P>,
+        E: From<ProstConvertError>,
+    {
+        self.try_into_native_stream_with(OnError::default())
+    }
+
+    /// Convert every message of the stream, handling the conversion errors with `on_error`.
+    fn try_into_native_stream_with<N>(self, on_error: OnError) -> TryIntoNativeStream<Self, N>
+    where
+        N: TryFromProto<P>,
+        E: From<ProstConvertError>,
+    {
+        TryIntoNativeStream {
+            stream: Box::pin(self),
+            on_error,
+            terminated: false,
+            native: PhantomData,
+        }
+    }
+}
+
+impl<S, P, E> TryIntoNativeStreamExt<P, E> for S where S: Stream<Item = Result<P, E>> {}
+
+/// A stream of proto messages converted into native values, see [`TryIntoNativeStreamExt`].
+pub struct TryIntoNativeStream<S, N> {
+    stream: Pin<Box<S>>,
+    on_error: OnError,
+    terminated: bool,
+    native: PhantomData<fn() -> N>,
+}
+
+impl<S, N> std::fmt::Debug for TryIntoNativeStream<S, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TryIntoNativeStream")
+            .field("on_error", &self.on_error)
+            .field("terminated", &self.terminated)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, P, E, N> Stream for TryIntoNativeStream<S, N>
+where
+    S: Stream<Item = Result<P, E>>,
+    N: TryFromProto<P>,
+    E: From<ProstConvertError>,
+{
+    type Item = Result<N, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.terminated {
+                return Poll::Ready(None);
+            }
+            let message = match self.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(message))) => message,
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            match (message.try_into_native(), self.on_error) {
+                (Ok(native), _) => return Poll::Ready(Some(Ok(native))),
+                (Err(_), OnError::Skip) => continue,
+                (Err(err), OnError::Yield) => return Poll::Ready(Some(Err(err.into()))),
+                (Err(err), OnError::Terminate) => {
+                    self.terminated = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.terminated {
+            return (0, Some(0));
+        }
+        match self.on_error {
+            OnError::Yield => self.stream.size_hint(),
+            OnError::Skip | OnError::Terminate => (0, self.stream.size_hint().1),
+        }
+    }
+}
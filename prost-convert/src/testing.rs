@@ -0,0 +1,145 @@
+//! Helpers checking that native values survive a conversion into proto and back.
+//!
+//! ```
+//! # use prost_convert::{FromNative, ProstConvertError, TryFromProto};
+//! # mod proto {
+//! #     pub struct User {
+//! #         pub name: String,
+//! #     }
+//! # }
+//! # #[derive(Clone, Debug, PartialEq)]
+//! # struct User {
+//! #     name: String,
+//! # }
+//! # impl TryFromProto<proto::User> for User {
+//! #     fn try_from_proto(value: proto::User) -> Result<Self, ProstConvertError> {
+//! #         Ok(Self { name: value.name })
+//! #     }
+//! # }
+//! # impl FromNative<User> for proto::User {
+//! #     fn from_native(value: User) -> Self {
+//! #         Self { name: value.name }
+//! #     }
+//! # }
+//! prost_convert::testing::assert_roundtrip::<proto::User, _>(User { name: "foo".into() });
+//! ```
+//!
+//! With the `proptest` feature, [`check_roundtrip`] checks the round trip of random native values.
+//! The derive macro emits such a test for types annotated with `#[prost_convert(roundtrip_test)]`.
+//!
+//! With the `prost` feature, [`fuzz_try_from_proto`] feeds arbitrary bytes to a conversion, for
+//! the fuzz targets of `cargo fuzz`.
+
+use std::fmt::Debug;
+
+use crate::{IntoProto, ProstConvertError, TryFromProto, TryIntoNative};
+
+/// Convert a native value into the proto type `P`, and back.
+pub fn roundtrip<P, N>(native: N) -> Result<N, ProstConvertError>
+where
+    N: IntoProto<P> + TryFromProto<P>,
+{
+    IntoProto::<P>::into_proto(native).try_into_native()
+}
+
+/// Assert that a native value is unchanged once converted into the proto type `P` and back.
+///
+/// # Panics
+///
+/// If the proto value can't be converted back, or the converted value is different.
+#[track_caller]
+pub fn assert_roundtrip<P, N>(native: N)
+where
+    N: IntoProto<P> + TryFromProto<P> + Clone + PartialEq + Debug,
+{
+    match roundtrip::<P, N>(native.clone()) {
+        Ok(converted) => assert_eq!(
+            converted, native,
+            "native value changed once converted into proto and back"
+        ),
+        Err(err) => panic!("failed to convert {native:?} back from proto: {err}"),
+    }
+}
+
+/// Check the round trip of random native values, generated by their `proptest::Arbitrary`
+/// implementation.
+///
+/// # Panics
+///
+/// With the smallest native value found whose round trip fails.
+#[cfg(feature = "proptest")]
+#[track_caller]
+pub fn check_roundtrip<P, N>()
+where
+    N: proptest::arbitrary::Arbitrary + IntoProto<P> + TryFromProto<P> + Clone + PartialEq,
+{
+    use proptest::test_runner::{TestCaseError, TestRunner};
+
+    let mut runner = TestRunner::default();
+    let result = runner.run(&proptest::arbitrary::any::<N>(), |native| {
+        match roundtrip::<P, N>(native.clone()) {
+            Ok(converted) if converted == native => Ok(()),
+            Ok(converted) => Err(TestCaseError::fail(format!(
+                "native value changed into {converted:?}"
+            ))),
+            Err(err) => Err(TestCaseError::fail(format!(
+                "failed to convert back from proto: {err}"
+            ))),
+        }
+    });
+    if let Err(err) = result {
+        panic!("round trip through proto failed: {err}");
+    }
+}
+
+/// Decode arbitrary bytes as the proto message `P`, and convert it into `N` and back into proto.
+///
+/// Invalid bytes and failed conversions are ignored: a fuzz target calling this function only
+/// checks that the conversions never panic.
+///
+/// ```
+/// # use prost_convert::{FromNative, ProstConvertError, TryFromProto};
+/// # mod proto {
+/// #     #[derive(Clone, PartialEq, prost::Message)]
+/// #     pub struct User {
+/// #         #[prost(string, tag = "1")]
+/// #         pub name: String,
+/// #     }
+/// # }
+/// # struct User {
+/// #     name: String,
+/// # }
+/// # impl TryFromProto<proto::User> for User {
+/// #     fn try_from_proto(value: proto::User) -> Result<Self, ProstConvertError> {
+/// #         Ok(Self { name: value.name })
+/// #     }
+/// # }
+/// # impl FromNative<User> for proto::User {
+/// #     fn from_native(value: User) -> Self {
+/// #         Self { name: value.name }
+/// #     }
+/// # }
+/// # macro_rules! fuzz_target {
+/// #     (|$data:ident: &[u8]| $body:block) => {
+/// #         let fuzz = |$data: &[u8]| $body;
+/// #         fuzz(b"\x0a\x03foo");
+/// #         fuzz(b"\xff");
+/// #     };
+/// # }
+/// fuzz_target!(|data: &[u8]| {
+///     prost_convert::testing::fuzz_try_from_proto::<proto::User, User>(data);
+/// });
+/// ```
+#[cfg(feature = "prost")]
+pub fn fuzz_try_from_proto<P, N>(data: &[u8])
+where
+    P: prost::Message + Default,
+    N: IntoProto<P> + TryFromProto<P>,
+{
+    let Ok(proto) = P::decode(data) else {
+        return;
+    };
+    if let Ok(native) = N::try_from_proto(proto) {
+        let _: P = native.into_proto();
+    }
+}
@@ -0,0 +1,189 @@
+//! Conversions at the boundary of a tonic service.
+//!
+//! Service handlers receive a `tonic::Request` of a proto message and return a
+//! `tonic::Response` of another one. These helpers convert them from and into native values, and
+//! a [`ProstConvertError`] into a `tonic::Status` sent back to the client.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use prost_convert::{FromNative, ProstConvertError, TryFromProto, TryIntoNative};
+//! # mod proto {
+//! #     pub struct GetUserRequest {
+//! #         pub id: String,
+//! #     }
+//! #     pub struct User {
+//! #         pub name: String,
+//! #     }
+//! # }
+//! # struct GetUserRequest {
+//! #     id: uuid::Uuid,
+//! # }
+//! # impl TryFromProto<proto::GetUserRequest> for GetUserRequest {
+//! #     fn try_from_proto(value: proto::GetUserRequest) -> Result<Self, ProstConvertError> {
+//! #         Ok(Self { id: value.id.try_into_native()? })
+//! #     }
+//! # }
+//! # #[derive(Clone)]
+//! # struct User {
+//! #     name: String,
+//! # }
+//! # impl FromNative<User> for proto::User {
+//! #     fn from_native(value: User) -> Self {
+//! #         Self { name: value.name }
+//! #     }
+//! # }
+//! struct Users {
+//!     users: HashMap<uuid::Uuid, User>,
+//! }
+//!
+//! impl Users {
+//!     async fn get_user(
+//!         &self,
+//!         request: tonic::Request<proto::GetUserRequest>,
+//!     ) -> Result<tonic::Response<proto::User>, tonic::Status> {
+//!         let (_metadata, request): (_, GetUserRequest) =
+//!             prost_convert::tonic::try_from_request(request)?;
+//!         let user = self
+//!             .users
+//!             .get(&request.id)
+//!             .ok_or_else(|| tonic::Status::not_found("no such user"))?;
+//!         Ok(prost_convert::tonic::into_response(user.clone()))
+//!     }
+//! }
+//! ```
+//!
+//! The native services generated by `prost-convert-build` use the other helpers of this module,
+//! which also convert the streams of streaming methods.
+
+// Tonic handlers and streams return a `Status`, boxing it would only make them unwrap it.
+#![allow(clippy::result_large_err)]
+
+use std::error::Error;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::tonic::{metadata::MetadataMap, Request, Response, Status};
+pub use async_trait::async_trait;
+pub use futures_core::Stream;
+
+use crate::stream::TryIntoNativeStreamExt;
+use crate::{IntoProto, ProstConvertError, TryFromProto, TryIntoNative};
+
+/// The stream of native messages received by a client-streaming method.
+pub type BoxStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+/// Convert the message of a request into a native value, and return it with the metadata of the
+/// request.
+///
+/// A message which can't be converted is rejected with `Status::invalid_argument`.
+pub fn try_from_request<P, N>(request: Request<P>) -> Result<(MetadataMap, N), Status>
+where
+    N: TryFromProto<P>,
+{
+    let (metadata, _extensions, message) = request.into_parts();
+    let native = message.try_into_native()?;
+    Ok((metadata, native))
+}
+
+/// Build a response whose message is converted from a native value.
+pub fn into_response<N, P>(native: N) -> Response<P>
+where
+    N: IntoProto<P>,
+{
+    Response::new(native.into_proto())
+}
+
+/// A message which can't be converted is an invalid argument of the call.
+///
+/// The message of the status lists every error, and their sources.
+impl From<ProstConvertError> for Status {
+    fn from(err: ProstConvertError) -> Self {
+        let mut message = format!("invalid message: {err}");
+        let mut source = err.source();
+        while let Some(err) = source {
+            message.push_str(&format!(": {err}"));
+            source = err.source();
+        }
+        Status::invalid_argument(message)
+    }
+}
+
+/// Convert the message of a request into a native value, keeping the metadata and extensions of
+/// the request.
+pub fn native_request<P, N>(request: Request<P>) -> Result<Request<N>, Status>
+where
+    N: TryFromProto<P>,
+{
+    let (metadata, extensions, message) = request.into_parts();
+    Ok(Request::from_parts(
+        metadata,
+        extensions,
+        message.try_into_native()?,
+    ))
+}
+
+/// Convert the stream of proto messages of a request into a stream of native values.
+///
+/// A message which can't be converted is an `invalid_argument` error of the stream.
+pub fn native_stream_request<S, P, N>(request: Request<S>) -> Request<BoxStream<N>>
+where
+    S: Stream<Item = Result<P, Status>> + Send + 'static,
+    N: TryFromProto<P> + Send + 'static,
+{
+    request.map(|stream| -> BoxStream<N> { Box::pin(stream.try_into_native_stream()) })
+}
+
+/// Convert the native message of a response into a proto one, keeping its metadata.
+pub fn proto_response<N, P>(response: Response<N>) -> Response<P>
+where
+    N: IntoProto<P>,
+{
+    response.map(IntoProto::into_proto)
+}
+
+/// Convert the stream of native values of a response into a stream of proto messages.
+pub fn proto_stream_response<S, P>(response: Response<S>) -> Response<ProtoStream<S, P>> {
+    response.map(ProtoStream::new)
+}
+
+/// A stream of native values converted into proto messages.
+pub struct ProtoStream<S, P> {
+    stream: Pin<Box<S>>,
+    proto: PhantomData<fn() -> P>,
+}
+
+impl<S, P> ProtoStream<S, P> {
+    /// Convert the values of `stream`.
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: Box::pin(stream),
+            proto: PhantomData,
+        }
+    }
+}
+
+impl<S, P> std::fmt::Debug for ProtoStream<S, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProtoStream").finish_non_exhaustive()
+    }
+}
+
+impl<S, N, P> Stream for ProtoStream<S, P>
+where
+    S: Stream<Item = Result<N, Status>>,
+    N: IntoProto<P>,
+{
+    type Item = Result<P, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream
+            .as_mut()
+            .poll_next(cx)
+            .map(|native| native.map(|native| native.map(IntoProto::into_proto)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
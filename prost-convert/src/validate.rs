@@ -0,0 +1,250 @@
+//! Declarative validation of proto fields.
+//!
+//! These functions back the `#[prost_convert(validate(...))]` field attribute of the derive
+//! macro. They are checked on the proto value, before it is converted, so that an oversized
+//! field is rejected before any work is done on it. An unset optional field is always valid.
+
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+
+use crate::ProstConvertError;
+
+/// A validation rule broken by a proto field.
+#[allow(missing_docs)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    #[error("length {actual} is lower than {min}")]
+    MinLen { min: usize, actual: usize },
+    #[error("length {actual} is greater than {max}")]
+    MaxLen { max: usize, actual: usize },
+    #[error("value doesn't match the pattern `{pattern}`")]
+    Pattern { pattern: &'static str },
+    #[error("value is out of the range `{range}`")]
+    Range { range: &'static str },
+    #[error("{actual} items is more than {max}")]
+    MaxItems { max: usize, actual: usize },
+}
+
+/// The value of a proto field, as seen by the validation rules.
+///
+/// Optional fields are validated only when they are set.
+pub trait FieldValue {
+    /// The type the rules are checked on.
+    type Value: ?Sized;
+
+    /// Return the value of the field, or `None` if it is unset.
+    fn field_value(&self) -> Option<&Self::Value>;
+}
+
+/// Values that have a length, counted in characters for strings and in elements otherwise.
+pub trait Length {
+    /// Return the length of the value.
+    fn length(&self) -> usize;
+}
+
+macro_rules! impl_field_value {
+    ( $($t:ty),* ) => {
+        $(
+            impl FieldValue for $t {
+                type Value = $t;
+
+                fn field_value(&self) -> Option<&Self::Value> {
+                    Some(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_field_value!(f32, f64, i32, i64, u32, u64, bool);
+
+impl FieldValue for String {
+    type Value = str;
+
+    fn field_value(&self) -> Option<&Self::Value> {
+        Some(self)
+    }
+}
+
+// Covers `bytes` and `repeated` fields.
+impl<T> FieldValue for Vec<T> {
+    type Value = [T];
+
+    fn field_value(&self) -> Option<&Self::Value> {
+        Some(self)
+    }
+}
+
+impl<K, V> FieldValue for HashMap<K, V> {
+    type Value = HashMap<K, V>;
+
+    fn field_value(&self) -> Option<&Self::Value> {
+        Some(self)
+    }
+}
+
+impl<T> FieldValue for Option<T>
+where
+    T: FieldValue,
+{
+    type Value = T::Value;
+
+    fn field_value(&self) -> Option<&Self::Value> {
+        self.as_ref().and_then(FieldValue::field_value)
+    }
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for HashMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+fn invalid(field: &'static str, violation: Violation) -> ProstConvertError {
+    ProstConvertError::InvalidField { field, violation }
+}
+
+/// Check that the length of `value` is at least `min`.
+pub fn min_len<F>(field: &'static str, value: &F, min: usize) -> Result<(), ProstConvertError>
+where
+    F: FieldValue,
+    F::Value: Length,
+{
+    match value.field_value().map(Length::length) {
+        Some(actual) if actual < min => Err(invalid(field, Violation::MinLen { min, actual })),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the length of `value` is at most `max`.
+pub fn max_len<F>(field: &'static str, value: &F, max: usize) -> Result<(), ProstConvertError>
+where
+    F: FieldValue,
+    F::Value: Length,
+{
+    match value.field_value().map(Length::length) {
+        Some(actual) if actual > max => Err(invalid(field, Violation::MaxLen { max, actual })),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the repeated or map field `value` has at most `max` elements.
+pub fn max_items<F>(field: &'static str, value: &F, max: usize) -> Result<(), ProstConvertError>
+where
+    F: FieldValue,
+    F::Value: Length,
+{
+    match value.field_value().map(Length::length) {
+        Some(actual) if actual > max => Err(invalid(field, Violation::MaxItems { max, actual })),
+        _ => Ok(()),
+    }
+}
+
+/// Check that `value` is inside `range`, `repr` being how the range is written by the user.
+pub fn range<F, R>(
+    field: &'static str,
+    value: &F,
+    range: R,
+    repr: &'static str,
+) -> Result<(), ProstConvertError>
+where
+    F: FieldValue,
+    F::Value: PartialOrd + Sized,
+    R: RangeBounds<F::Value>,
+{
+    match value.field_value() {
+        Some(value) if !range.contains(value) => {
+            Err(invalid(field, Violation::Range { range: repr }))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check the `pattern` rule of the derive macro, compiling the expression only once.
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __validate_pattern {
+    ($field:expr, $value:expr, $pattern:expr) => {{
+        static PATTERN: $crate::validate::Pattern = $crate::validate::Pattern::new($pattern);
+        $crate::validate::pattern($field, $value, &PATTERN)
+    }};
+}
+
+// Without it, the derive macro would fail on a missing `validate::Pattern`.
+#[cfg(not(feature = "regex"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __validate_pattern {
+    ($($tt:tt)*) => {
+        ::core::compile_error!(
+            "the `pattern` validation rule requires the `regex` feature of prost-convert"
+        )
+    };
+}
+
+/// A regular expression compiled the first time it is used.
+///
+/// It is meant to be stored in a `static`, so that the expression is compiled only once.
+#[cfg(feature = "regex")]
+#[derive(Debug)]
+pub struct Pattern {
+    pattern: &'static str,
+    regex: std::sync::OnceLock<Result<regex::Regex, regex::Error>>,
+}
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    /// Create a pattern from a regular expression.
+    pub const fn new(pattern: &'static str) -> Self {
+        Self {
+            pattern,
+            regex: std::sync::OnceLock::new(),
+        }
+    }
+}
+
+/// Check that the string `value` matches `pattern`.
+#[cfg(feature = "regex")]
+pub fn pattern<F>(
+    field: &'static str,
+    value: &F,
+    pattern: &Pattern,
+) -> Result<(), ProstConvertError>
+where
+    F: FieldValue,
+    F::Value: AsRef<str>,
+{
+    let Some(value) = value.field_value() else {
+        return Ok(());
+    };
+    // The derive macro checks the expression at compile time, so this can only fail for a
+    // handwritten pattern.
+    let regex = pattern
+        .regex
+        .get_or_init(|| regex::Regex::new(pattern.pattern))
+        .as_ref()
+        .map_err(|err| ProstConvertError::ValidationError(err.clone().into()))?;
+    if regex.is_match(value.as_ref()) {
+        Ok(())
+    } else {
+        Err(invalid(
+            field,
+            Violation::Pattern {
+                pattern: pattern.pattern,
+            },
+        ))
+    }
+}
@@ -1,3 +1,6 @@
+// Impls are written inside the tests to mimic what the derive macro generates.
+#![allow(non_local_definitions)]
+
 use prost_convert::{FromNative, IntoProto, ProstConvertError, TryFromProto, TryIntoNative};
 
 macro_rules! try_from_proto_scalar {